@@ -3,25 +3,36 @@ use std::marker::PhantomData;
 use halo2_proofs::{
     arithmetic::Field,
     circuit::{AssignedCell, Chip, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
     poly::Rotation,
 };
 
+use super::instructions::{ArithmeticInstructions, PublicOutputInstructions, square_sum};
+
 /// 示例：实现一个简单的平方和芯片
 /// 功能：计算 a² + b² = c
 
 // 1️⃣ 定义配置结构
 #[derive(Debug, Clone)]
-struct SquareSumConfig {
-    advice: [Column<Advice>; 3], // 3个advice列
-    instance: Column<Instance>,  // 实例列
-    s_square: Selector,          // 平方选择器
-    s_add: Selector,             // 加法选择器
+pub struct SquareSumConfig {
+    advice: [Column<Advice>; 3],  // 3个advice列
+    instance: Column<Instance>,   // 实例列
+    s_square: Selector,           // 平方选择器
+    s_add: Selector,              // 加法选择器
+    product: Option<ProductGate>, // 乘法门，只有 `configure_with_product` 才会创建
+}
+
+/// 乘法门 a × b × const = c 用到的常数列和选择器
+#[derive(Debug, Clone)]
+struct ProductGate {
+    constant: Column<Fixed>,
+    s_mul: Selector,
 }
 
 // 2️⃣ 定义芯片结构
+/// 平方和芯片：用 `configure` 分配约束，`construct` 得到芯片后调用各运算方法
 #[derive(Debug, Clone)]
-struct SquareSumChip<F: Field> {
+pub struct SquareSumChip<F: Field> {
     config: SquareSumConfig,
     _marker: PhantomData<F>,
 }
@@ -43,7 +54,7 @@ impl<F: Field> Chip<F> for SquareSumChip<F> {
 // 4️⃣ 实现芯片的核心功能
 impl<F: Field> SquareSumChip<F> {
     /// 构造函数
-    fn construct(config: SquareSumConfig) -> Self {
+    pub fn construct(config: SquareSumConfig) -> Self {
         Self {
             config,
             _marker: PhantomData,
@@ -51,7 +62,7 @@ impl<F: Field> SquareSumChip<F> {
     }
 
     /// 配置函数 - 定义电路约束
-    fn configure(
+    pub fn configure(
         meta: &mut ConstraintSystem<F>,
         advice: [Column<Advice>; 3],
         instance: Column<Instance>,
@@ -89,11 +100,37 @@ impl<F: Field> SquareSumChip<F> {
             instance,
             s_square,
             s_add,
+            product: None,
         }
     }
 
+    /// 与 `configure` 相同，另外创建乘法门以支持 `mul_with_constant`
+    pub fn configure_with_product(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+        constant: Column<Fixed>,
+    ) -> SquareSumConfig {
+        let mut config = Self::configure(meta, advice, instance);
+        let s_mul = meta.selector();
+
+        // 创建乘法门: a * b * const = c
+        meta.create_gate("mul", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let c = meta.query_advice(advice[2], Rotation::cur());
+            let constant = meta.query_fixed(constant);
+            let s_mul = meta.query_selector(s_mul);
+
+            vec![s_mul * (a * b * constant - c)]
+        });
+
+        config.product = Some(ProductGate { constant, s_mul });
+        config
+    }
+
     /// 加载私有输入
-    fn load_private(
+    pub fn load_private(
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<F>,
@@ -107,7 +144,7 @@ impl<F: Field> SquareSumChip<F> {
     }
 
     /// 计算平方: a²
-    fn square(
+    pub fn square(
         &self,
         mut layouter: impl Layouter<F>,
         value: AssignedCell<F, F>,
@@ -128,7 +165,7 @@ impl<F: Field> SquareSumChip<F> {
     }
 
     /// 加法运算: a + b = c
-    fn add(
+    pub fn add(
         &self,
         mut layouter: impl Layouter<F>,
         a: AssignedCell<F, F>,
@@ -150,8 +187,42 @@ impl<F: Field> SquareSumChip<F> {
         )
     }
 
+    /// 乘法运算: a × b × const = c
+    ///
+    /// 只有用 `configure_with_product` 配置的芯片才有乘法门，否则返回 `Error::Synthesis`
+    pub fn mul_with_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+        constant: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let product = config.product.as_ref().ok_or(Error::Synthesis)?;
+
+        layouter.assign_region(
+            || "mul with constant",
+            |mut region| {
+                product.s_mul.enable(&mut region, 0)?;
+
+                region.assign_fixed(
+                    || "constant",
+                    product.constant,
+                    0,
+                    || Value::known(constant),
+                )?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                let result = a.value().zip(b.value()).map(|(a, b)| *a * *b * constant);
+                region.assign_advice(|| "a×b×const", config.advice[2], 0, || result)
+            },
+        )
+    }
+
     /// 暴露公共输出
-    fn expose_public(
+    pub fn expose_public(
         &self,
         mut layouter: impl Layouter<F>,
         cell: AssignedCell<F, F>,
@@ -162,11 +233,59 @@ impl<F: Field> SquareSumChip<F> {
     }
 }
 
+// 指令trait直接转发到上面的方法
+impl<F: Field> ArithmeticInstructions<F> for SquareSumChip<F> {
+    type Num = AssignedCell<F, F>;
+
+    fn load_private(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<Self::Num, Error> {
+        SquareSumChip::load_private(self, layouter, value)
+    }
+
+    fn square(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error> {
+        SquareSumChip::square(self, layouter, a)
+    }
+
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        SquareSumChip::add(self, layouter, a, b)
+    }
+
+    fn mul_with_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error> {
+        SquareSumChip::mul_with_constant(self, layouter, a, b, constant)
+    }
+}
+
+impl<F: Field> PublicOutputInstructions<F> for SquareSumChip<F> {
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        cell: Self::Num,
+        row: usize,
+    ) -> Result<(), Error> {
+        SquareSumChip::expose_public(self, layouter, cell, row)
+    }
+}
+
 // 5️⃣ 定义电路结构
+/// 公开输出 c = a² + b²，可直接交给 `proof::pipeline` 生成真实证明
 #[derive(Default)]
-struct SquareSumCircuit<F: Field> {
-    a: Value<F>,
-    b: Value<F>,
+pub struct SquareSumCircuit<F: Field> {
+    pub a: Value<F>,
+    pub b: Value<F>,
 }
 
 // 6️⃣ 实现Circuit trait (必须实现的接口)
@@ -200,33 +319,19 @@ impl<F: Field> Circuit<F> for SquareSumCircuit<F> {
         // 构造芯片实例
         let chip = SquareSumChip::construct(config);
 
-        // 步骤1: 加载私有输入
-        let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
-        let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
-
-        // 步骤2: 计算平方
-        let a_squared = chip.square(layouter.namespace(|| "a²"), a)?;
-        let b_squared = chip.square(layouter.namespace(|| "b²"), b)?;
-
-        // 步骤3: 计算和
-        let result = chip.add(layouter.namespace(|| "a² + b²"), a_squared, b_squared)?;
-
-        // 步骤4: 暴露公共输出
-        chip.expose_public(layouter.namespace(|| "expose result"), result, 0)?;
-
-        Ok(())
+        // 加载输入、计算平方、求和并暴露公共输出
+        square_sum(&chip, layouter.namespace(|| "a² + b²"), self.a, self.b)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stats::CircuitStats;
     use halo2_proofs::{dev::MockProver, pasta::Fp};
 
     #[test]
     fn test_square_sum_circuit() {
-        let k = 4;
-
         // 准备输入: a = 3, b = 4
         let a = Fp::from(3);
         let b = Fp::from(4);
@@ -238,6 +343,9 @@ mod tests {
             b: Value::known(b),
         };
 
+        // 根据电路实际占用的行数选择k
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+
         // 公共输入
         let public_inputs = vec![c];
 
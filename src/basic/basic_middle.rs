@@ -15,6 +15,10 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+use super::instructions::{
+    ArithmeticInstructions, PublicOutputInstructions, square_sum_with_product,
+};
+
 /// 优化后的电路设计:
 /// | a0   | a1   | a2   | const | s_add | s_mul | s_sq |
 /// |------|------|------|-------|-------|-------|------|
@@ -22,9 +26,16 @@ use halo2_proofs::{
 /// |  b   |      |      |       |   0   |   0   |  1   | <- b²  
 /// |  a   |  b   |      | const |   0   |   1   |  0   | <- a×b×const
 /// | a²   | b²   | ab×c |       |   1   |   0   |  0   | <- final sum
+///
+/// 线性组合门（系数在三个额外的fixed列中）：
+/// | a0   | a1   | a2   | k0 | k1 | k2 | s_lin |
+/// |------|------|------|----|----|----|-------|
+/// | x0   | x1   | x2   | c0 | c1 | c2 |   1   | <- c0·x0 + c1·x1 + c2·x2
+/// | acc  | x3   | x4   | 1  | c3 | c4 |   1   | <- 项数超过3时换行累加
+/// | sum  |      |      |    |    |    |   0   |
 
 #[derive(Debug, Clone)]
-struct OptimizedFieldConfig {
+pub struct OptimizedFieldConfig {
     /// 三个advice列用于不同的操作
     advice: [Column<Advice>; 3],
     /// instance列用于公开输出
@@ -35,23 +46,31 @@ struct OptimizedFieldConfig {
     s_add: Selector, // 加法门：a0 + a1 + a2 = next_row_a0
     s_mul: Selector, // 乘法门：a0 * a1 * const = a2
     s_sq: Selector,  // 平方门：a0 * a0 = next_row_a0
+    /// 线性组合的系数
+    coeffs: [Column<Fixed>; 3],
+    s_lin: Selector, // 线性组合门：k0*a0 + k1*a1 + k2*a2 = next_row_a0
 }
 
+/// 紧凑布局的域运算芯片，运算结果以 [`Number`] 返回
 #[derive(Debug, Clone)]
-struct OptimizedFieldChip<F: Field> {
+pub struct OptimizedFieldChip<F: Field> {
     config: OptimizedFieldConfig,
     _marker: PhantomData<F>,
 }
 
 impl<F: Field> OptimizedFieldChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config) -> Self {
+    /// 构造函数
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
         OptimizedFieldChip {
             config,
             _marker: PhantomData,
         }
     }
 
-    fn configure(
+    /// 配置函数：三个advice列、公开输出的instance列、存放常数的fixed列
+    ///
+    /// 线性组合门的三个系数列在这里分配
+    pub fn configure(
         meta: &mut ConstraintSystem<F>,
         advice: [Column<Advice>; 3],
         instance: Column<Instance>,
@@ -67,6 +86,12 @@ impl<F: Field> OptimizedFieldChip<F> {
         let s_add = meta.selector();
         let s_mul = meta.selector();
         let s_sq = meta.selector();
+        let s_lin = meta.selector();
+        let coeffs = [
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+        ];
 
         // 加法门：a0 + a1 + a2 = next_row_a0
         meta.create_gate("add_gate", |meta| {
@@ -99,6 +124,20 @@ impl<F: Field> OptimizedFieldChip<F> {
             vec![s_sq * (a0.clone() * a0 - a0_sq)]
         });
 
+        // 线性组合门：k0*a0 + k1*a1 + k2*a2 = next_row_a0
+        meta.create_gate("linear_gate", |meta| {
+            let s_lin = meta.query_selector(s_lin);
+            let sum = meta.query_advice(advice[0], Rotation::next());
+            let combination = advice
+                .iter()
+                .zip(coeffs.iter())
+                .map(|(&a, &k)| meta.query_advice(a, Rotation::cur()) * meta.query_fixed(k))
+                .reduce(|acc, term| acc + term)
+                .unwrap();
+
+            vec![s_lin * (combination - sum)]
+        });
+
         OptimizedFieldConfig {
             advice,
             instance,
@@ -106,8 +145,114 @@ impl<F: Field> OptimizedFieldChip<F> {
             s_add,
             s_mul,
             s_sq,
+            coeffs,
+            s_lin,
         }
     }
+
+    /// 加权和：Σ coeff_i · x_i
+    ///
+    /// 第一行放3项，之后每行把上一行的结果放在a0（系数1），再放2项；
+    /// `terms` 为空时返回 `Error::Synthesis`
+    pub fn weighted_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[(F, Number<F>)],
+    ) -> Result<Number<F>, Error> {
+        if terms.is_empty() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "weighted sum",
+            |mut region| {
+                let mut remaining = terms.iter();
+                let mut acc: Option<AssignedCell<F, F>> = None;
+                let mut row = 0;
+
+                loop {
+                    // 第一行三个槽都放项，之后a0是上一行的累加结果
+                    let mut slots: Vec<Option<&(F, Number<F>)>> = Vec::with_capacity(3);
+                    if acc.is_some() {
+                        slots.push(None);
+                    }
+                    while slots.len() < 3 {
+                        slots.push(remaining.next());
+                    }
+                    if acc.is_some() && slots[1].is_none() {
+                        break;
+                    }
+
+                    config.s_lin.enable(&mut region, row)?;
+                    let mut sum = acc
+                        .as_ref()
+                        .map_or(Value::known(F::ZERO), |acc| acc.value().copied());
+                    for (i, slot) in slots.iter().enumerate() {
+                        let coeff = match slot {
+                            Some((coeff, x)) => {
+                                x.0.copy_advice(
+                                    || format!("x{i}"),
+                                    &mut region,
+                                    config.advice[i],
+                                    row,
+                                )?;
+                                sum = sum + x.0.value().map(|x| *x * coeff);
+                                *coeff
+                            }
+                            // a0上的累加结果已经在上一行分配
+                            None if i == 0 => F::ONE,
+                            None => {
+                                region.assign_advice(
+                                    || format!("unused {i}"),
+                                    config.advice[i],
+                                    row,
+                                    || Value::known(F::ZERO),
+                                )?;
+                                F::ZERO
+                            }
+                        };
+                        region.assign_fixed(
+                            || format!("k{i}"),
+                            config.coeffs[i],
+                            row,
+                            || Value::known(coeff),
+                        )?;
+                    }
+
+                    row += 1;
+                    acc = Some(region.assign_advice(|| "sum", config.advice[0], row, || sum)?);
+                }
+
+                Ok(Number(acc.expect("at least one row")))
+            },
+        )
+    }
+
+    /// 减法：a - b
+    pub fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.weighted_sum(layouter, &[(F::ONE, a), (-F::ONE, b)])
+    }
+
+    /// 取负：-a
+    pub fn neg(&self, layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
+        self.weighted_sum(layouter, &[(-F::ONE, a)])
+    }
+
+    /// 数乘：k · a
+    pub fn scale(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        k: F,
+    ) -> Result<Number<F>, Error> {
+        self.weighted_sum(layouter, &[(k, a)])
+    }
 }
 
 impl<F: Field> Chip<F> for OptimizedFieldChip<F> {
@@ -123,12 +268,13 @@ impl<F: Field> Chip<F> for OptimizedFieldChip<F> {
     }
 }
 
-#[derive(Clone)]
-struct Number<F: Field>(AssignedCell<F, F>);
+/// `OptimizedFieldChip` 分配的值，内部的cell可以直接参与copy约束
+#[derive(Debug, Clone)]
+pub struct Number<F: Field>(pub AssignedCell<F, F>);
 
 impl<F: Field> OptimizedFieldChip<F> {
     /// 加载私有输入
-    fn load_private(
+    pub fn load_private(
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<F>,
@@ -146,7 +292,7 @@ impl<F: Field> OptimizedFieldChip<F> {
     }
 
     /// 计算平方：a²
-    fn square(&self, mut layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
+    pub fn square(&self, mut layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
         let config = self.config();
 
         layouter.assign_region(
@@ -163,8 +309,37 @@ impl<F: Field> OptimizedFieldChip<F> {
         )
     }
 
+    /// 两数相加：a + b + 0，第三个输入固定为常数0
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                config.s_add.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                region.assign_advice_from_constant(|| "zero", config.advice[2], 0, F::ZERO)?;
+
+                let sum =
+                    a.0.value()
+                        .zip(b.0.value())
+                        .map(|(a_val, b_val)| *a_val + *b_val);
+                region
+                    .assign_advice(|| "a+b", config.advice[0], 1, || sum)
+                    .map(Number)
+            },
+        )
+    }
+
     /// 乘法运算：a × b × const
-    fn mul_with_constant(
+    pub fn mul_with_constant(
         &self,
         mut layouter: impl Layouter<F>,
         a: Number<F>,
@@ -201,7 +376,7 @@ impl<F: Field> OptimizedFieldChip<F> {
     }
 
     /// 三数相加：a + b + c
-    fn add_three(
+    pub fn add_three(
         &self,
         mut layouter: impl Layouter<F>,
         a: Number<F>,
@@ -233,7 +408,7 @@ impl<F: Field> OptimizedFieldChip<F> {
     }
 
     /// 暴露公共输出
-    fn expose_public(
+    pub fn expose_public(
         &self,
         mut layouter: impl Layouter<F>,
         num: Number<F>,
@@ -244,19 +419,80 @@ impl<F: Field> OptimizedFieldChip<F> {
     }
 }
 
+// 指令trait直接转发到上面的方法
+impl<F: Field> ArithmeticInstructions<F> for OptimizedFieldChip<F> {
+    type Num = Number<F>;
+
+    fn load_private(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<Number<F>, Error> {
+        OptimizedFieldChip::load_private(self, layouter, value)
+    }
+
+    fn square(&self, layouter: impl Layouter<F>, a: Number<F>) -> Result<Number<F>, Error> {
+        OptimizedFieldChip::square(self, layouter, a)
+    }
+
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        OptimizedFieldChip::add(self, layouter, a, b)
+    }
+
+    fn mul_with_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        constant: F,
+    ) -> Result<Number<F>, Error> {
+        OptimizedFieldChip::mul_with_constant(self, layouter, a, b, constant)
+    }
+
+    fn add_three(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        c: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        OptimizedFieldChip::add_three(self, layouter, a, b, c)
+    }
+}
+
+impl<F: Field> PublicOutputInstructions<F> for OptimizedFieldChip<F> {
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        num: Number<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        OptimizedFieldChip::expose_public(self, layouter, num, row)
+    }
+}
+
 #[derive(Default)]
-struct OptimizedCircuit<F: Field> {
-    constant: F,
-    a: Value<F>,
-    b: Value<F>,
+pub struct OptimizedCircuit<F: Field> {
+    pub constant: F,
+    pub a: Value<F>,
+    pub b: Value<F>,
 }
 
 impl<F: Field> Circuit<F> for OptimizedCircuit<F> {
     type Config = OptimizedFieldConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
+    /// `constant` 写在fixed列中，属于电路结构，keygen时必须保留
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self {
+            constant: self.constant,
+            ..Self::default()
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -278,44 +514,25 @@ impl<F: Field> Circuit<F> for OptimizedCircuit<F> {
     ) -> Result<(), Error> {
         let field_chip = OptimizedFieldChip::<F>::construct(config);
 
-        // 加载私有输入
-        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
-        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
-
-        // 计算 a² 和 b²
-        let a_sq = field_chip.square(layouter.namespace(|| "a²"), a.clone())?;
-        let b_sq = field_chip.square(layouter.namespace(|| "b²"), b.clone())?;
-
-        // 计算 a × b × const
-        let ab_const = field_chip.mul_with_constant(
-            layouter.namespace(|| "a×b×const"),
-            a,
-            b,
-            self.constant,
-        )?;
-
-        // 计算最终结果：a² + b² + (a×b×const)
-        let result = field_chip.add_three(
+        // a² + b² + (a×b×const)，最后一步使用三输入加法门
+        square_sum_with_product(
+            &field_chip,
             layouter.namespace(|| "a²+b²+ab×const"),
-            a_sq,
-            b_sq,
-            ab_const,
-        )?;
-
-        // 暴露公共输出
-        field_chip.expose_public(layouter.namespace(|| "expose result"), result, 0)
+            self.a,
+            self.b,
+            self.constant,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stats::CircuitStats;
     use halo2_proofs::{dev::MockProver, pasta::Fp};
 
     #[test]
     fn test_optimized_circuit() {
-        let k = 6; // 稍微增大以容纳更多行
-
         // 准备输入
         let constant = Fp::from(3);
         let a = Fp::from(4);
@@ -332,6 +549,9 @@ mod tests {
             b: Value::known(b),
         };
 
+        // 根据电路实际占用的行数选择k
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+
         // 公共输入
         let public_inputs = vec![expected_output];
 
@@ -347,6 +567,110 @@ mod tests {
         println!("优化电路测试通过！");
     }
 
+    /// 用线性组合门计算 2a+3b-c、a-b、-a、5a 以及7项加权和
+    #[derive(Default)]
+    struct LinearCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+        c: Value<Fp>,
+        xs: [Value<Fp>; 7],
+    }
+
+    impl Circuit<Fp> for LinearCircuit {
+        type Config = OptimizedFieldConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            OptimizedCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = OptimizedFieldChip::construct(config);
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+            let c = chip.load_private(layouter.namespace(|| "c"), self.c)?;
+
+            let combination = chip.weighted_sum(
+                layouter.namespace(|| "2a+3b-c"),
+                &[
+                    (Fp::from(2), a.clone()),
+                    (Fp::from(3), b.clone()),
+                    (-Fp::one(), c),
+                ],
+            )?;
+            let difference = chip.sub(layouter.namespace(|| "a-b"), a.clone(), b)?;
+            let negated = chip.neg(layouter.namespace(|| "-a"), a.clone())?;
+            let scaled = chip.scale(layouter.namespace(|| "5a"), a, Fp::from(5))?;
+
+            let mut terms = vec![];
+            for (i, &x) in self.xs.iter().enumerate() {
+                let x = chip.load_private(layouter.namespace(|| format!("x{i}")), x)?;
+                terms.push((Fp::from(i as u64 + 1), x));
+            }
+            let weighted = chip.weighted_sum(layouter.namespace(|| "Σ(i+1)·x_i"), &terms)?;
+
+            // 空的加权和返回错误而不是panic
+            assert!(matches!(
+                chip.weighted_sum(layouter.namespace(|| "empty"), &[]),
+                Err(Error::Synthesis)
+            ));
+
+            for (row, num) in [combination, difference, negated, scaled, weighted]
+                .into_iter()
+                .enumerate()
+            {
+                chip.expose_public(layouter.namespace(|| format!("out {row}")), num, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_linear_combinations() {
+        let (a, b, c) = (Fp::from(7), Fp::from(11), Fp::from(40));
+        let xs: Vec<Fp> = (10..17).map(Fp::from).collect();
+        let circuit = LinearCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+            c: Value::known(c),
+            xs: xs
+                .iter()
+                .map(|&x| Value::known(x))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        };
+
+        let weighted = xs
+            .iter()
+            .enumerate()
+            .fold(Fp::zero(), |acc, (i, &x)| acc + Fp::from(i as u64 + 1) * x);
+        // 2·7 + 3·11 - 40 = 7，a - b 为负数
+        let public = vec![Fp::from(7), a - b, -a, Fp::from(35), weighted];
+
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        let prover = MockProver::run(k, &circuit, vec![public.clone()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // 任意一个输出错误都失败
+        for row in 0..public.len() {
+            let mut wrong = public.clone();
+            wrong[row] += Fp::one();
+            let prover = MockProver::run(k, &circuit, vec![wrong]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        println!("线性组合测试通过！");
+    }
+
     #[cfg(feature = "dev-graph")]
     #[test]
     fn test_optimized_circuit_visual() {
@@ -0,0 +1,298 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Chip, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// ==============================================
+/// 布尔值与条件选择Chip
+/// ==============================================
+///
+/// `Bool` 只能通过布尔约束得到（`BoolChip::assert_bool` / `witness_bool`，
+/// 或输出本身已被约束为0/1的芯片，例如 `IsZeroChip`），
+/// 所以 `select` 不需要再检查条件是否为布尔值。
+///
+/// | x/cond | a | b | out                 | s_bool | s_select |
+/// |--------|---|---|---------------------|--------|----------|
+/// |   x    |   |   |                     |   1    |    0     | <- x·(1-x) = 0
+/// |  cond  | a | b | cond·a + (1-cond)·b |   0    |    1     |
+
+/// 已被约束为0或1的cell
+#[derive(Debug, Clone)]
+pub struct Bool<F: Field>(AssignedCell<F, F>);
+
+impl<F: Field> Bool<F> {
+    /// 仅供输出已被约束为0/1的芯片使用
+    pub(crate) fn from_constrained(cell: AssignedCell<F, F>) -> Self {
+        Bool(cell)
+    }
+
+    pub fn cell(&self) -> &AssignedCell<F, F> {
+        &self.0
+    }
+
+    pub fn into_cell(self) -> AssignedCell<F, F> {
+        self.0
+    }
+
+    pub fn value(&self) -> Value<bool> {
+        self.0.value().map(|v| *v == F::ONE)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BoolConfig {
+    advice: [Column<Advice>; 4], // [cond, a, b, out]
+    s_bool: Selector,
+    s_select: Selector,
+}
+
+/// 布尔约束和条件选择芯片，输入可以是任意芯片的 `AssignedCell`
+#[derive(Debug, Clone)]
+pub struct BoolChip<F: Field> {
+    config: BoolConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Chip<F> for BoolChip<F> {
+    type Config = BoolConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> BoolChip<F> {
+    pub fn construct(config: BoolConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 4]) -> BoolConfig {
+        let s_bool = meta.selector();
+        let s_select = meta.selector();
+
+        // 启用equality约束
+        for c in &advice {
+            meta.enable_equality(*c);
+        }
+
+        // 布尔门：x * (1 - x) = 0
+        meta.create_gate("bool_gate", |meta| {
+            let x = meta.query_advice(advice[0], Rotation::cur());
+            let s_bool = meta.query_selector(s_bool);
+
+            vec![s_bool * x.clone() * (Expression::Constant(F::ONE) - x)]
+        });
+
+        // 选择门：cond * a + (1 - cond) * b = out
+        meta.create_gate("select_gate", |meta| {
+            let cond = meta.query_advice(advice[0], Rotation::cur());
+            let a = meta.query_advice(advice[1], Rotation::cur());
+            let b = meta.query_advice(advice[2], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+            let s_select = meta.query_selector(s_select);
+
+            vec![s_select * (cond * (a - b.clone()) + b - out)]
+        });
+
+        BoolConfig {
+            advice,
+            s_bool,
+            s_select,
+        }
+    }
+
+    /// 约束已分配的cell为0或1
+    pub fn assert_bool(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+    ) -> Result<Bool<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "assert bool",
+            |mut region| {
+                config.s_bool.enable(&mut region, 0)?;
+                x.copy_advice(|| "x", &mut region, config.advice[0], 0)
+                    .map(Bool)
+            },
+        )
+    }
+
+    /// 加载私有布尔值
+    pub fn witness_bool(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<bool>,
+    ) -> Result<Bool<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "witness bool",
+            |mut region| {
+                config.s_bool.enable(&mut region, 0)?;
+                region
+                    .assign_advice(
+                        || "bool",
+                        config.advice[0],
+                        0,
+                        || value.map(|b| if b { F::ONE } else { F::ZERO }),
+                    )
+                    .map(Bool)
+            },
+        )
+    }
+
+    /// 条件选择：cond ? a : b
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: &Bool<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                config.s_select.enable(&mut region, 0)?;
+
+                cond.0
+                    .copy_advice(|| "cond", &mut region, config.advice[0], 0)?;
+                a.copy_advice(|| "a", &mut region, config.advice[1], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[2], 0)?;
+
+                let out = cond
+                    .value()
+                    .zip(a.value())
+                    .zip(b.value())
+                    .map(|((cond, a), b)| if cond { *a } else { *b });
+                region.assign_advice(|| "cond ? a : b", config.advice[3], 0, || out)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        basic::{
+            basic_middle::{Number, OptimizedCircuit, OptimizedFieldChip, OptimizedFieldConfig},
+            is_zero::{IsZeroChip, IsZeroConfig},
+        },
+        stats::CircuitStats,
+    };
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    /// 公开 cond ? a² : b² 以及 (a == b) ? a : c
+    #[derive(Default)]
+    struct SelectCircuit {
+        cond: Value<Fp>,
+        a: Value<Fp>,
+        b: Value<Fp>,
+        c: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for SelectCircuit {
+        type Config = (OptimizedFieldConfig, IsZeroConfig, BoolConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let field = OptimizedCircuit::<Fp>::configure(meta);
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let constant = meta.fixed_column();
+            (
+                field,
+                IsZeroChip::configure(meta, advice, constant),
+                BoolChip::configure(meta, advice),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (field, is_zero, boolean): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let field = OptimizedFieldChip::construct(field);
+            let is_zero = IsZeroChip::construct(is_zero);
+            let boolean = BoolChip::construct(boolean);
+
+            let cond = field.load_private(layouter.namespace(|| "cond"), self.cond)?;
+            let a = field.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = field.load_private(layouter.namespace(|| "b"), self.b)?;
+            let c = field.load_private(layouter.namespace(|| "c"), self.c)?;
+
+            // 其他芯片的cell经过布尔约束后才能作为条件
+            let cond = boolean.assert_bool(layouter.namespace(|| "cond ∈ {0, 1}"), &cond.0)?;
+            let a_sq = field.square(layouter.namespace(|| "a²"), a.clone())?;
+            let b_sq = field.square(layouter.namespace(|| "b²"), b.clone())?;
+            let first = boolean.select(
+                layouter.namespace(|| "cond ? a² : b²"),
+                &cond,
+                &a_sq.0,
+                &b_sq.0,
+            )?;
+
+            // IsZero的输出本身就是 `Bool`
+            let equal = is_zero.is_equal(layouter.namespace(|| "a == b"), &a.0, &b.0)?;
+            let second =
+                boolean.select(layouter.namespace(|| "a == b ? a : c"), &equal, &a.0, &c.0)?;
+
+            field.expose_public(layouter.namespace(|| "first"), Number(first), 0)?;
+            field.expose_public(layouter.namespace(|| "second"), Number(second), 1)
+        }
+    }
+
+    fn run(cond: u64, a: u64, b: u64, public: [u64; 2]) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = SelectCircuit {
+            cond: Value::known(Fp::from(cond)),
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            c: Value::known(Fp::from(100)),
+        };
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        let public = public.iter().map(|&v| Fp::from(v)).collect();
+        MockProver::run(k, &circuit, vec![public]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(run(1, 3, 4, [9, 100]), Ok(()));
+        assert_eq!(run(0, 3, 4, [16, 100]), Ok(()));
+        assert_eq!(run(1, 5, 5, [25, 5]), Ok(()));
+        assert!(run(1, 3, 4, [16, 100]).is_err());
+
+        // 非布尔条件在 `assert_bool` 处被拒绝，无法得到 2·a² - b² = 2
+        assert!(run(2, 3, 4, [2, 100]).is_err());
+
+        println!("条件选择测试通过！");
+    }
+}
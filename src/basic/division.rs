@@ -0,0 +1,414 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+/// ==============================================
+/// 求逆与除法Chip
+/// ==============================================
+///
+/// q = a / b 不能只约束 b·q = a：a = b = 0 时任意q都满足。
+/// 因此同时见证 b⁻¹ 并约束 b·b⁻¹ = 1，b = 0 时没有任何witness能通过。
+///
+/// 安全除法在 b = 0 时不失败，而是返回标志位（IsZero的写法）：
+///   flag = 1 - b·inv,  b·flag = 0,  flag·q = 0,  b·q = a·(1 - flag)
+/// b ≠ 0 时 flag = 0、q = a/b；b = 0 时 flag = 1、q = 0。
+///
+/// | num | den | quotient | den_inv | flag | s_div | s_safe_div |
+/// |-----|-----|----------|---------|------|-------|------------|
+/// |  a  |  b  |   a/b    |   b⁻¹   |      |   1   |     0      |
+/// |  a  |  b  |  a/b或0  | b⁻¹或0  | 0/1  |   0   |     1      |
+
+#[derive(Debug, Clone)]
+pub struct DivConfig {
+    advice: [Column<Advice>; 5], // [num, den, quotient, den_inv, flag]
+    s_div: Selector,
+    s_safe_div: Selector,
+}
+
+/// 除法芯片，输入输出都是 `AssignedCell`
+#[derive(Debug, Clone)]
+pub struct DivChip<F: Field> {
+    config: DivConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Chip<F> for DivChip<F> {
+    type Config = DivConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> DivChip<F> {
+    pub fn construct(config: DivConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `constant` 用于求逆时的常数1
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 5],
+        constant: Column<Fixed>,
+    ) -> DivConfig {
+        let s_div = meta.selector();
+        let s_safe_div = meta.selector();
+
+        // 启用equality和constant约束
+        meta.enable_constant(constant);
+        for c in &advice {
+            meta.enable_equality(*c);
+        }
+
+        // 除法门：den * quotient = num，den * den_inv = 1
+        meta.create_gate("div_gate", |meta| {
+            let num = meta.query_advice(advice[0], Rotation::cur());
+            let den = meta.query_advice(advice[1], Rotation::cur());
+            let quotient = meta.query_advice(advice[2], Rotation::cur());
+            let den_inv = meta.query_advice(advice[3], Rotation::cur());
+            let s_div = meta.query_selector(s_div);
+
+            vec![
+                s_div.clone() * (den.clone() * quotient - num),
+                s_div * (den * den_inv - Expression::Constant(F::ONE)),
+            ]
+        });
+
+        // 安全除法门：den = 0 时 flag = 1 且 quotient = 0
+        meta.create_gate("safe_div_gate", |meta| {
+            let num = meta.query_advice(advice[0], Rotation::cur());
+            let den = meta.query_advice(advice[1], Rotation::cur());
+            let quotient = meta.query_advice(advice[2], Rotation::cur());
+            let den_inv = meta.query_advice(advice[3], Rotation::cur());
+            let flag = meta.query_advice(advice[4], Rotation::cur());
+            let s = meta.query_selector(s_safe_div);
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * (flag.clone() - (one.clone() - den.clone() * den_inv)),
+                s.clone() * (den.clone() * flag.clone()),
+                s.clone() * (flag.clone() * quotient.clone()),
+                s * (den * quotient - num * (one - flag)),
+            ]
+        });
+
+        DivConfig {
+            advice,
+            s_div,
+            s_safe_div,
+        }
+    }
+
+    /// 加载私有输入（放在被除数列）
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", config.advice[0], 0, || value),
+        )
+    }
+
+    /// 求逆：a⁻¹，a = 0 时无法满足约束
+    pub fn invert(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let one = layouter.assign_region(
+            || "constant one",
+            |mut region| region.assign_advice_from_constant(|| "one", config.advice[0], 0, F::ONE),
+        )?;
+        self.div(layouter, one, a)
+    }
+
+    /// 除法：a / b，b = 0 时无法满足约束
+    pub fn div(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let b_inv = b.value().map(|b| b.invert().unwrap_or(F::ZERO));
+        let quotient = a.value().zip(b_inv).map(|(a, b_inv)| *a * b_inv);
+
+        layouter.assign_region(
+            || "divide",
+            |mut region| self.assign_div(&mut region, &a, &b, quotient, b_inv),
+        )
+    }
+
+    /// 安全除法：返回 (quotient, flag)，b = 0 时 quotient = 0、flag = 1
+    pub fn safe_div(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let b_inv = b.value().map(|b| b.invert().unwrap_or(F::ZERO));
+        let quotient = a.value().zip(b_inv).map(|(a, b_inv)| *a * b_inv);
+        let flag = b
+            .value()
+            .map(|b| if b.is_zero_vartime() { F::ONE } else { F::ZERO });
+
+        layouter.assign_region(
+            || "safe divide",
+            |mut region| self.assign_safe_div(&mut region, &a, &b, quotient, b_inv, flag),
+        )
+    }
+
+    /// 在第0行复制a、b并分配商和逆
+    fn assign_div(
+        &self,
+        region: &mut Region<'_, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        quotient: Value<F>,
+        b_inv: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        config.s_div.enable(region, 0)?;
+
+        a.copy_advice(|| "a", region, config.advice[0], 0)?;
+        b.copy_advice(|| "b", region, config.advice[1], 0)?;
+        region.assign_advice(|| "b⁻¹", config.advice[3], 0, || b_inv)?;
+        region.assign_advice(|| "a/b", config.advice[2], 0, || quotient)
+    }
+
+    /// 在第0行复制a、b并分配商、逆和零标志
+    fn assign_safe_div(
+        &self,
+        region: &mut Region<'_, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        quotient: Value<F>,
+        b_inv: Value<F>,
+        flag: Value<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        config.s_safe_div.enable(region, 0)?;
+
+        a.copy_advice(|| "a", region, config.advice[0], 0)?;
+        b.copy_advice(|| "b", region, config.advice[1], 0)?;
+        region.assign_advice(|| "b⁻¹ or 0", config.advice[3], 0, || b_inv)?;
+        let quotient = region.assign_advice(|| "a/b or 0", config.advice[2], 0, || quotient)?;
+        let flag = region.assign_advice(|| "b == 0", config.advice[4], 0, || flag)?;
+        Ok((quotient, flag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, Instance},
+    };
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> (DivConfig, Column<Instance>) {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let constant = meta.fixed_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        (DivChip::configure(meta, advice, constant), instance)
+    }
+
+    /// 公开 a/b、b⁻¹ 以及安全除法的商和标志
+    #[derive(Default)]
+    struct DivCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+        /// b = 0 时只做安全除法
+        safe_only: bool,
+    }
+
+    impl Circuit<Fp> for DivCircuit {
+        type Config = (DivConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                safe_only: self.safe_only,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = DivChip::construct(config);
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), self.b)?;
+
+            let (quotient, flag) =
+                chip.safe_div(layouter.namespace(|| "a / b safe"), a.clone(), b.clone())?;
+            let mut outputs = vec![quotient, flag];
+            if !self.safe_only {
+                outputs.push(chip.div(layouter.namespace(|| "a / b"), a, b.clone())?);
+                outputs.push(chip.invert(layouter.namespace(|| "1 / b"), b)?);
+            }
+
+            for (row, cell) in outputs.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn run(circuit: &impl Circuit<Fp>, public: Vec<Fp>) -> Result<(), Vec<VerifyFailure>> {
+        let k = CircuitStats::measure(circuit).unwrap().minimal_k;
+        MockProver::run(k, circuit, vec![public]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_division() {
+        let (a, b) = (Fp::from(42), Fp::from(5));
+        let b_inv = b.invert().unwrap();
+        let circuit = DivCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+            safe_only: false,
+        };
+        assert_eq!(
+            run(&circuit, vec![a * b_inv, Fp::zero(), a * b_inv, b_inv]),
+            Ok(())
+        );
+        assert!(run(&circuit, vec![a * b_inv, Fp::zero(), a * b_inv, b]).is_err());
+
+        // 除以0：安全除法返回 (0, 1)
+        let circuit = DivCircuit {
+            a: Value::known(a),
+            b: Value::known(Fp::zero()),
+            safe_only: true,
+        };
+        assert_eq!(run(&circuit, vec![Fp::zero(), Fp::one()]), Ok(()));
+        assert!(run(&circuit, vec![Fp::zero(), Fp::zero()]).is_err());
+
+        // 普通除法和求逆遇到0时无法满足约束
+        let circuit = DivCircuit {
+            a: Value::known(a),
+            b: Value::known(Fp::zero()),
+            safe_only: false,
+        };
+        assert!(
+            run(
+                &circuit,
+                vec![Fp::zero(), Fp::one(), Fp::zero(), Fp::zero()]
+            )
+            .is_err()
+        );
+
+        println!("除法测试通过！");
+    }
+
+    /// 恶意prover：对 b = 0 任意伪造商、逆和标志
+    struct ForgedCircuit {
+        a: Value<Fp>,
+        quotient: Value<Fp>,
+        b_inv: Value<Fp>,
+        /// Some时伪造安全除法的标志
+        flag: Option<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for ForgedCircuit {
+        type Config = (DivConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        /// 是否为安全除法决定了布局，因此保留 `flag` 的有无
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: Value::unknown(),
+                quotient: Value::unknown(),
+                b_inv: Value::unknown(),
+                flag: self.flag.map(|_| Value::unknown()),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            (config, _): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = DivChip::construct(config);
+            let a = chip.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "b"), Value::known(Fp::zero()))?;
+
+            let (quotient, b_inv) = (self.quotient, self.b_inv);
+            layouter.assign_region(
+                || "forged division",
+                |mut region| match self.flag {
+                    None => chip
+                        .assign_div(&mut region, &a, &b, quotient, b_inv)
+                        .map(|_| ()),
+                    Some(flag) => chip
+                        .assign_safe_div(&mut region, &a, &b, quotient, b_inv, flag)
+                        .map(|_| ()),
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_cannot_fake_inverse_of_zero() {
+        let forged = |a: u64, quotient: u64, b_inv: u64, flag: Option<u64>| ForgedCircuit {
+            a: Value::known(Fp::from(a)),
+            quotient: Value::known(Fp::from(quotient)),
+            b_inv: Value::known(Fp::from(b_inv)),
+            flag: flag.map(|flag| Value::known(Fp::from(flag))),
+        };
+
+        // 0的逆：b·b⁻¹ = 0 ≠ 1
+        for b_inv in [0, 1, 7] {
+            assert!(run(&forged(1, b_inv, b_inv, None), vec![]).is_err());
+        }
+        // 0 / 0：b·q = a 对任意q成立，但逆的约束仍然失败
+        assert!(run(&forged(0, 9, 3, None), vec![]).is_err());
+
+        // 安全除法：标志不能设为0，商不能非0
+        assert!(run(&forged(5, 0, 3, Some(0)), vec![]).is_err());
+        assert!(run(&forged(5, 4, 0, Some(1)), vec![]).is_err());
+        assert!(run(&forged(0, 4, 0, Some(1)), vec![]).is_err());
+        // 诚实的witness可以通过
+        assert_eq!(run(&forged(5, 0, 0, Some(1)), vec![]), Ok(()));
+
+        println!("伪造0的逆测试通过！");
+    }
+}
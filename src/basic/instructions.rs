@@ -0,0 +1,252 @@
+/// ==============================================
+/// 算术指令接口
+/// ==============================================
+///
+/// `SquareSumChip`、`OptimizedFieldChip` 和 `MultiChip`（平方/加法/乘法三芯片组合）
+/// 提供同一组运算，只是布局不同。电路只依赖这里的trait编写，
+/// 换一个芯片就能在不改synthesize代码的情况下对比不同布局。
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Chip, Layouter, Value},
+    plonk::Error,
+};
+
+/// 基本算术运算
+pub trait ArithmeticInstructions<F: Field>: Chip<F> {
+    /// 芯片中已分配数值的表示
+    type Num: Clone;
+
+    /// 加载私有输入
+    fn load_private(&self, layouter: impl Layouter<F>, value: Value<F>)
+    -> Result<Self::Num, Error>;
+
+    /// 平方：a²
+    fn square(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error>;
+
+    /// 加法：a + b
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// 带常数的乘法：a × b × constant
+    fn mul_with_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error>;
+
+    /// 三数相加：a + b + c
+    ///
+    /// 默认用两次 `add` 实现，有三输入加法门的芯片可以覆盖为一次运算
+    fn add_three(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        c: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let ab = self.add(layouter.namespace(|| "a + b"), a, b)?;
+        self.add(layouter.namespace(|| "(a + b) + c"), ab, c)
+    }
+}
+
+/// 公开输出
+pub trait PublicOutputInstructions<F: Field>: ArithmeticInstructions<F> {
+    /// 将数值约束为instance列第 `row` 行的公开值
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        num: Self::Num,
+        row: usize,
+    ) -> Result<(), Error>;
+}
+
+/// ==============================================
+/// 通用电路主体
+/// ==============================================
+
+/// 计算 a² + b² 并公开到第0行
+pub fn square_sum<F: Field, C: PublicOutputInstructions<F>>(
+    chip: &C,
+    mut layouter: impl Layouter<F>,
+    a: Value<F>,
+    b: Value<F>,
+) -> Result<(), Error> {
+    let a = chip.load_private(layouter.namespace(|| "load a"), a)?;
+    let b = chip.load_private(layouter.namespace(|| "load b"), b)?;
+
+    let a_squared = chip.square(layouter.namespace(|| "a²"), a)?;
+    let b_squared = chip.square(layouter.namespace(|| "b²"), b)?;
+
+    let result = chip.add(layouter.namespace(|| "a² + b²"), a_squared, b_squared)?;
+    chip.expose_public(layouter.namespace(|| "expose result"), result, 0)
+}
+
+/// 计算 a² + b² + a×b×constant 并公开到第0行
+pub fn square_sum_with_product<F: Field, C: PublicOutputInstructions<F>>(
+    chip: &C,
+    mut layouter: impl Layouter<F>,
+    a: Value<F>,
+    b: Value<F>,
+    constant: F,
+) -> Result<(), Error> {
+    let a = chip.load_private(layouter.namespace(|| "load a"), a)?;
+    let b = chip.load_private(layouter.namespace(|| "load b"), b)?;
+
+    let a_squared = chip.square(layouter.namespace(|| "a²"), a.clone())?;
+    let b_squared = chip.square(layouter.namespace(|| "b²"), b.clone())?;
+    let ab_const = chip.mul_with_constant(layouter.namespace(|| "a×b×const"), a, b, constant)?;
+
+    let result = chip.add_three(
+        layouter.namespace(|| "a²+b²+ab×const"),
+        a_squared,
+        b_squared,
+        ab_const,
+    )?;
+    chip.expose_public(layouter.namespace(|| "expose result"), result, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        basic::{
+            basic_chip::{SquareSumChip, SquareSumConfig},
+            basic_middle::{OptimizedCircuit, OptimizedFieldChip, OptimizedFieldConfig},
+            multi_chip_design::MultiChipCircuit,
+        },
+        stats::CircuitStats,
+    };
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem},
+    };
+
+    /// 在带乘法门的平方和芯片上运行带乘积的电路主体
+    #[derive(Default)]
+    struct SquareSumProductCircuit {
+        constant: Fp,
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for SquareSumProductCircuit {
+        type Config = SquareSumConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let instance = meta.instance_column();
+            let constant = meta.fixed_column();
+            SquareSumChip::configure_with_product(meta, advice, instance, constant)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = SquareSumChip::construct(config);
+            square_sum_with_product(&chip, layouter, self.a, self.b, self.constant)
+        }
+    }
+
+    /// 在紧凑布局芯片上运行不带乘积的电路主体
+    #[derive(Default)]
+    struct OptimizedSquareSumCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for OptimizedSquareSumCircuit {
+        type Config = OptimizedFieldConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            OptimizedCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = OptimizedFieldChip::construct(config);
+            square_sum(&chip, layouter, self.a, self.b)
+        }
+    }
+
+    fn check<C: Circuit<Fp>>(circuit: &C, expected: Fp) {
+        let k = CircuitStats::measure(circuit).unwrap().minimal_k;
+
+        let prover = MockProver::run(k, circuit, vec![vec![expected]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let prover = MockProver::run(k, circuit, vec![vec![expected + Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_same_body_on_every_layout() {
+        let constant = Fp::from(3);
+        let a = Fp::from(4);
+        let b = Fp::from(5);
+        let expected = a * a + b * b + a * b * constant;
+
+        // 同一个电路主体，三种布局
+        check(
+            &SquareSumProductCircuit {
+                constant,
+                a: Value::known(a),
+                b: Value::known(b),
+            },
+            expected,
+        );
+        check(
+            &OptimizedCircuit {
+                constant,
+                a: Value::known(a),
+                b: Value::known(b),
+            },
+            expected,
+        );
+        check(
+            &MultiChipCircuit {
+                constant,
+                a: Value::known(a),
+                b: Value::known(b),
+            },
+            expected,
+        );
+
+        // 紧凑布局上的加法用常数0补齐三输入加法门
+        check(
+            &OptimizedSquareSumCircuit {
+                a: Value::known(a),
+                b: Value::known(b),
+            },
+            a * a + b * b,
+        );
+
+        println!("通用电路主体在三种布局上测试通过！");
+    }
+}
@@ -0,0 +1,288 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::boolean::Bool;
+
+/// ==============================================
+/// IsZero / 相等判断Chip
+/// ==============================================
+///
+/// 对 d = a - b 见证 inv（d ≠ 0 时为 d⁻¹，否则任意），约束：
+///   out = 1 - d·inv,  d·out = 0
+/// d ≠ 0 时第二式迫使 out = 0（inv = d⁻¹），d = 0 时第一式迫使 out = 1，
+/// 所以out一定是布尔值，prover无法通过篡改inv改变结果。
+///
+/// | a | b | inv        | out    | s_is_zero |
+/// |---|---|------------|--------|-----------|
+/// | a | b | (a-b)⁻¹或0 | a == b |     1     |
+///
+/// `is_zero(x)` 就是 `is_equal(x, 0)`，0来自常数列。
+
+#[derive(Debug, Clone)]
+pub struct IsZeroConfig {
+    advice: [Column<Advice>; 4], // [a, b, inv, out]
+    s_is_zero: Selector,
+}
+
+/// 判零芯片，输入是任意芯片的 `AssignedCell`，输出是 `Bool`
+#[derive(Debug, Clone)]
+pub struct IsZeroChip<F: Field> {
+    config: IsZeroConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Chip<F> for IsZeroChip<F> {
+    type Config = IsZeroConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> IsZeroChip<F> {
+    pub fn construct(config: IsZeroConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `constant` 用于 `is_zero` 中的常数0
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        constant: Column<Fixed>,
+    ) -> IsZeroConfig {
+        let s_is_zero = meta.selector();
+
+        // 启用equality和constant约束
+        meta.enable_constant(constant);
+        for c in &advice {
+            meta.enable_equality(*c);
+        }
+
+        // 判零门：out = 1 - (a-b)·inv，(a-b)·out = 0
+        meta.create_gate("is_zero_gate", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let inv = meta.query_advice(advice[2], Rotation::cur());
+            let out = meta.query_advice(advice[3], Rotation::cur());
+            let s = meta.query_selector(s_is_zero);
+
+            let diff = a - b;
+            vec![
+                s.clone() * (out.clone() - (Expression::Constant(F::ONE) - diff.clone() * inv)),
+                s * (diff * out),
+            ]
+        });
+
+        IsZeroConfig { advice, s_is_zero }
+    }
+
+    /// a == b 时返回1，否则返回0
+    pub fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<Bool<F>, Error> {
+        let diff = a.value().zip(b.value()).map(|(a, b)| *a - *b);
+        let (inv, out) = witness(diff);
+
+        layouter.assign_region(
+            || "is equal",
+            |mut region| {
+                let config = self.config();
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                self.assign_is_zero(&mut region, a, inv, out)
+            },
+        )
+    }
+
+    /// x == 0 时返回1，否则返回0
+    pub fn is_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+    ) -> Result<Bool<F>, Error> {
+        let (inv, out) = witness(x.value().copied());
+
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                let config = self.config();
+                region.assign_advice_from_constant(|| "zero", config.advice[1], 0, F::ZERO)?;
+                self.assign_is_zero(&mut region, x, inv, out)
+            },
+        )
+    }
+
+    /// 在第0行复制a并分配inv和out（b由调用方分配）
+    fn assign_is_zero(
+        &self,
+        region: &mut Region<'_, F>,
+        a: &AssignedCell<F, F>,
+        inv: Value<F>,
+        out: Value<F>,
+    ) -> Result<Bool<F>, Error> {
+        let config = self.config();
+        config.s_is_zero.enable(region, 0)?;
+
+        a.copy_advice(|| "a", region, config.advice[0], 0)?;
+        region.assign_advice(|| "inv", config.advice[2], 0, || inv)?;
+        region
+            .assign_advice(|| "out", config.advice[3], 0, || out)
+            .map(Bool::from_constrained)
+    }
+}
+
+/// 诚实的 (inv, out)：d ≠ 0 时 (d⁻¹, 0)，否则 (0, 1)
+fn witness<F: Field>(diff: Value<F>) -> (Value<F>, Value<F>) {
+    let inv = diff.map(|d| d.invert().unwrap_or(F::ZERO));
+    let out = diff.map(|d| if d.is_zero_vartime() { F::ONE } else { F::ZERO });
+    (inv, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        basic::basic_middle::{Number, OptimizedCircuit, OptimizedFieldChip, OptimizedFieldConfig},
+        stats::CircuitStats,
+    };
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::Circuit,
+    };
+
+    /// 在 `OptimizedFieldChip` 的cell上判断相等，结果再交给该芯片计算 10·[a == b] + [a == 0]
+    #[derive(Default)]
+    struct EqualityCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+        /// Some时用给定的 (inv, out) 代替 a == b 的诚实witness
+        tampered: Option<(Fp, Fp)>,
+    }
+
+    impl Circuit<Fp> for EqualityCircuit {
+        type Config = (OptimizedFieldConfig, IsZeroConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let field = OptimizedCircuit::<Fp>::configure(meta);
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let constant = meta.fixed_column();
+            (field, IsZeroChip::configure(meta, advice, constant))
+        }
+
+        fn synthesize(
+            &self,
+            (field, is_zero): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let field = OptimizedFieldChip::construct(field);
+            let is_zero = IsZeroChip::construct(is_zero);
+
+            let a = field.load_private(layouter.namespace(|| "a"), self.a)?;
+            let b = field.load_private(layouter.namespace(|| "b"), self.b)?;
+
+            let equal = match self.tampered {
+                None => is_zero.is_equal(layouter.namespace(|| "a == b"), &a.0, &b.0)?,
+                Some((inv, out)) => layouter.assign_region(
+                    || "tampered a == b",
+                    |mut region| {
+                        b.0.copy_advice(|| "b", &mut region, is_zero.config().advice[1], 0)?;
+                        is_zero.assign_is_zero(
+                            &mut region,
+                            &a.0,
+                            Value::known(inv),
+                            Value::known(out),
+                        )
+                    },
+                )?,
+            };
+            let a_is_zero = is_zero.is_zero(layouter.namespace(|| "a == 0"), &a.0)?;
+
+            let result = field.weighted_sum(
+                layouter.namespace(|| "10·eq + zero"),
+                &[
+                    (Fp::from(10), Number(equal.into_cell())),
+                    (Fp::one(), Number(a_is_zero.into_cell())),
+                ],
+            )?;
+            field.expose_public(layouter.namespace(|| "result"), result, 0)
+        }
+    }
+
+    fn run(
+        a: u64,
+        b: u64,
+        tampered: Option<(Fp, Fp)>,
+        public: u64,
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = EqualityCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            tampered,
+        };
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        MockProver::run(k, &circuit, vec![vec![Fp::from(public)]])
+            .unwrap()
+            .verify()
+    }
+
+    #[test]
+    fn test_is_equal() {
+        assert_eq!(run(7, 7, None, 10), Ok(()));
+        assert_eq!(run(7, 8, None, 0), Ok(()));
+        assert_eq!(run(0, 0, None, 11), Ok(()));
+        assert_eq!(run(0, 5, None, 1), Ok(()));
+        assert!(run(7, 8, None, 10).is_err());
+
+        println!("相等判断测试通过！");
+    }
+
+    #[test]
+    fn test_is_zero_rejects_tampered_inverse() {
+        let diff = Fp::from(7) - Fp::from(8);
+
+        // a ≠ b：诚实的inv可以通过
+        assert_eq!(
+            run(7, 8, Some((diff.invert().unwrap(), Fp::zero())), 0),
+            Ok(())
+        );
+        // 把inv设为0，想让out = 1
+        assert!(run(7, 8, Some((Fp::zero(), Fp::one())), 10).is_err());
+        // 错误的inv，out仍为0
+        assert!(run(7, 8, Some((Fp::from(3), Fp::zero())), 0).is_err());
+        // a = b：不论inv取什么，out都不能为0
+        assert!(run(7, 7, Some((Fp::from(3), Fp::zero())), 0).is_err());
+        assert!(run(7, 7, Some((Fp::zero(), Fp::zero())), 0).is_err());
+        // a = b 时inv可以任意，结果仍为1
+        assert_eq!(run(7, 7, Some((Fp::from(3), Fp::one())), 10), Ok(()));
+
+        println!("篡改逆元测试通过！");
+    }
+}
@@ -1,3 +1,7 @@
 pub mod basic_chip;
 pub mod basic_middle;
+pub mod boolean;
+pub mod division;
+pub mod instructions;
+pub mod is_zero;
 pub mod multi_chip_design;
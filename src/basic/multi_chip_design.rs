@@ -7,18 +7,23 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+use super::instructions::{
+    ArithmeticInstructions, PublicOutputInstructions, square_sum_with_product,
+};
+
 /// ==============================================
 /// 1. 平方Chip - 专门处理平方运算
 /// ==============================================
 
 #[derive(Debug, Clone)]
-struct SquareConfig {
+pub struct SquareConfig {
     advice: [Column<Advice>; 2], // [input, output]
     s_square: Selector,
 }
 
+/// 平方芯片，输入输出都是 `AssignedCell`
 #[derive(Debug, Clone)]
-struct SquareChip<F: Field> {
+pub struct SquareChip<F: Field> {
     config: SquareConfig,
     _marker: PhantomData<F>,
 }
@@ -37,14 +42,14 @@ impl<F: Field> Chip<F> for SquareChip<F> {
 }
 
 impl<F: Field> SquareChip<F> {
-    fn construct(config: SquareConfig) -> Self {
+    pub fn construct(config: SquareConfig) -> Self {
         Self {
             config,
             _marker: PhantomData,
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 2]) -> SquareConfig {
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 2]) -> SquareConfig {
         let s_square = meta.selector();
 
         // 启用equality约束
@@ -64,8 +69,22 @@ impl<F: Field> SquareChip<F> {
         SquareConfig { advice, s_square }
     }
 
+    /// 加载私有输入（放在输入列）
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load private",
+            |mut region| region.assign_advice(|| "private input", config.advice[0], 0, || value),
+        )
+    }
+
     /// 计算平方：input² = output
-    fn square(
+    pub fn square(
         &self,
         mut layouter: impl Layouter<F>,
         input: AssignedCell<F, F>,
@@ -91,13 +110,14 @@ impl<F: Field> SquareChip<F> {
 /// ==============================================
 
 #[derive(Debug, Clone)]
-struct AddConfig {
+pub struct AddConfig {
     advice: [Column<Advice>; 4], // [a, b, c, sum]
     s_add: Selector,
 }
 
+/// 三数加法芯片
 #[derive(Debug, Clone)]
-struct AddChip<F: Field> {
+pub struct AddChip<F: Field> {
     config: AddConfig,
     _marker: PhantomData<F>,
 }
@@ -116,14 +136,14 @@ impl<F: Field> Chip<F> for AddChip<F> {
 }
 
 impl<F: Field> AddChip<F> {
-    fn construct(config: AddConfig) -> Self {
+    pub fn construct(config: AddConfig) -> Self {
         Self {
             config,
             _marker: PhantomData,
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 4]) -> AddConfig {
+    pub fn configure(meta: &mut ConstraintSystem<F>, advice: [Column<Advice>; 4]) -> AddConfig {
         let s_add = meta.selector();
 
         // 启用equality约束
@@ -146,7 +166,7 @@ impl<F: Field> AddChip<F> {
     }
 
     /// 三数相加：a + b + c = sum
-    fn add_three(
+    pub fn add_three(
         &self,
         mut layouter: impl Layouter<F>,
         a: AssignedCell<F, F>,
@@ -174,6 +194,37 @@ impl<F: Field> AddChip<F> {
             },
         )
     }
+
+    /// 两数相加：a + b + 0
+    ///
+    /// 第三个输入从常数0复制而来，约束系统中需要有启用了 `enable_constant` 的fixed列
+    /// （`MulChip::configure` 会启用它的常数列）
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: AssignedCell<F, F>,
+        b: AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "add two numbers",
+            |mut region| {
+                config.s_add.enable(&mut region, 0)?;
+
+                a.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                region.assign_advice_from_constant(|| "zero", config.advice[2], 0, F::ZERO)?;
+
+                let sum_value = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a_val, b_val)| *a_val + *b_val);
+
+                region.assign_advice(|| "a+b", config.advice[3], 0, || sum_value)
+            },
+        )
+    }
 }
 
 /// ==============================================
@@ -181,14 +232,15 @@ impl<F: Field> AddChip<F> {
 /// ==============================================
 
 #[derive(Debug, Clone)]
-struct MulConfig {
+pub struct MulConfig {
     advice: [Column<Advice>; 3], // [a, b, product]
     constant: Column<Fixed>,
     s_mul: Selector,
 }
 
+/// 带常数的乘法芯片
 #[derive(Debug, Clone)]
-struct MulChip<F: Field> {
+pub struct MulChip<F: Field> {
     config: MulConfig,
     _marker: PhantomData<F>,
 }
@@ -207,14 +259,14 @@ impl<F: Field> Chip<F> for MulChip<F> {
 }
 
 impl<F: Field> MulChip<F> {
-    fn construct(config: MulConfig) -> Self {
+    pub fn construct(config: MulConfig) -> Self {
         Self {
             config,
             _marker: PhantomData,
         }
     }
 
-    fn configure(
+    pub fn configure(
         meta: &mut ConstraintSystem<F>,
         advice: [Column<Advice>; 3],
         constant: Column<Fixed>,
@@ -246,7 +298,7 @@ impl<F: Field> MulChip<F> {
     }
 
     /// 乘法运算：a × b × constant = product
-    fn mul_with_constant(
+    pub fn mul_with_constant(
         &self,
         mut layouter: impl Layouter<F>,
         a: AssignedCell<F, F>,
@@ -287,11 +339,103 @@ impl<F: Field> MulChip<F> {
 /// ==============================================
 
 #[derive(Debug, Clone)]
-struct MultiChipConfig {
-    square_config: SquareConfig,
-    add_config: AddConfig,
-    mul_config: MulConfig,
-    instance: Column<Instance>,
+pub struct MultiChipConfig {
+    pub square_config: SquareConfig,
+    pub add_config: AddConfig,
+    pub mul_config: MulConfig,
+    pub instance: Column<Instance>,
+}
+
+/// 组合芯片：把三个独立的Chip包装成一组算术指令
+#[derive(Debug, Clone)]
+pub struct MultiChip<F: Field> {
+    config: MultiChipConfig,
+    square_chip: SquareChip<F>,
+    add_chip: AddChip<F>,
+    mul_chip: MulChip<F>,
+}
+
+impl<F: Field> Chip<F> for MultiChip<F> {
+    type Config = MultiChipConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: Field> MultiChip<F> {
+    /// 由组合配置构造三个子芯片
+    pub fn construct(config: MultiChipConfig) -> Self {
+        Self {
+            square_chip: SquareChip::construct(config.square_config.clone()),
+            add_chip: AddChip::construct(config.add_config.clone()),
+            mul_chip: MulChip::construct(config.mul_config.clone()),
+            config,
+        }
+    }
+}
+
+impl<F: Field> ArithmeticInstructions<F> for MultiChip<F> {
+    type Num = AssignedCell<F, F>;
+
+    /// 加载私有输入（放在平方chip的输入列）
+    fn load_private(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<Self::Num, Error> {
+        self.square_chip.load_private(layouter, value)
+    }
+
+    fn square(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error> {
+        self.square_chip.square(layouter, a)
+    }
+
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.add_chip.add(layouter, a, b)
+    }
+
+    fn mul_with_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error> {
+        self.mul_chip.mul_with_constant(layouter, a, b, constant)
+    }
+
+    /// 直接使用加法chip的三输入加法门
+    fn add_three(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        c: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.add_chip.add_three(layouter, a, b, c)
+    }
+}
+
+impl<F: Field> PublicOutputInstructions<F> for MultiChip<F> {
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Self::Num,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.cell(), self.config.instance, row)
+    }
 }
 
 /// ==============================================
@@ -299,18 +443,22 @@ struct MultiChipConfig {
 /// ==============================================
 
 #[derive(Default)]
-struct MultiChipCircuit<F: Field> {
-    constant: F,
-    a: Value<F>,
-    b: Value<F>,
+pub struct MultiChipCircuit<F: Field> {
+    pub constant: F,
+    pub a: Value<F>,
+    pub b: Value<F>,
 }
 
 impl<F: Field> Circuit<F> for MultiChipCircuit<F> {
     type Config = MultiChipConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
+    /// `constant` 写在fixed列中，属于电路结构，keygen时必须保留
     fn without_witnesses(&self) -> Self {
-        Self::default()
+        Self {
+            constant: self.constant,
+            ..Self::default()
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
@@ -352,61 +500,29 @@ impl<F: Field> Circuit<F> for MultiChipCircuit<F> {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        // 构造三个独立的chip
-        let square_chip = SquareChip::construct(config.square_config.clone());
-        let add_chip = AddChip::construct(config.add_config.clone());
-        let mul_chip = MulChip::construct(config.mul_config.clone());
-
-        // 创建临时的advice列用于加载输入
-        let temp_advice = config.square_config.advice[0];
-
-        // 加载私有输入
-        let a = layouter.assign_region(
-            || "load a",
-            |mut region| region.assign_advice(|| "private input a", temp_advice, 0, || self.a),
-        )?;
-
-        let b = layouter.assign_region(
-            || "load b",
-            |mut region| region.assign_advice(|| "private input b", temp_advice, 0, || self.b),
-        )?;
-
-        // 🔷 使用平方chip计算 a² 和 b²
-        let a_squared = square_chip.square(layouter.namespace(|| "compute a²"), a.clone())?;
-        let b_squared = square_chip.square(layouter.namespace(|| "compute b²"), b.clone())?;
-
-        // 🔶 使用乘法chip计算 a × b × constant
-        let ab_const = mul_chip.mul_with_constant(
-            layouter.namespace(|| "compute a×b×const"),
-            a,
-            b,
+        // 三个独立的chip包装成一组算术指令
+        let chip = MultiChip::construct(config);
+
+        // 🔷 平方chip计算 a² 和 b²，🔶 乘法chip计算 a × b × constant，
+        // 🔹 加法chip计算最终结果: a² + b² + (a×b×const)
+        square_sum_with_product(
+            &chip,
+            layouter.namespace(|| "a²+b²+ab×const"),
+            self.a,
+            self.b,
             self.constant,
-        )?;
-
-        // 🔹 使用加法chip计算最终结果: a² + b² + (a×b×const)
-        let result = add_chip.add_three(
-            layouter.namespace(|| "compute final sum"),
-            a_squared,
-            b_squared,
-            ab_const,
-        )?;
-
-        // 暴露公共输出
-        layouter.constrain_instance(result.cell(), config.instance, 0)?;
-
-        Ok(())
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stats::CircuitStats;
     use halo2_proofs::{dev::MockProver, pasta::Fp};
 
     #[test]
     fn test_multi_chip_circuit() {
-        let k = 8; // 增大以容纳更多chip的运算
-
         // 准备输入
         let constant = Fp::from(3);
         let a = Fp::from(4);
@@ -429,6 +545,9 @@ mod tests {
             b: Value::known(b),
         };
 
+        // 根据电路实际占用的行数选择k
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+
         // 公共输入
         let public_inputs = vec![expected_output];
 
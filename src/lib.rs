@@ -1,2 +1,12 @@
+//! halo2 示例芯片与电路
+//!
+//! - `basic`：算术芯片（平方和、紧凑布局、多芯片组合）
+//! - `lookup`：lookup table 与范围检查
+//! - `proof`：真实证明的生成、缓存与封装
+//! - `prelude`：下游电路组合芯片时常用的类型
 pub mod basic;
 pub mod lookup;
+pub mod prelude;
+pub mod proof;
+pub mod registry;
+pub mod stats;
@@ -0,0 +1,259 @@
+/// ==============================================
+/// 按行排列的二进制范围检查
+/// ==============================================
+///
+/// `BinaryRangeConfig` 把32个二进制位放在同一行的32个advice列里。
+/// 这里改为每行一位，只用两列：位列和累加器列，位数由调用时决定。
+/// 从最高位开始累加：acc_0 = b_0，acc_i = 2 · acc_{i-1} + b_i，最后一行的累加器就是被检查的值。
+///
+/// | bit   | acc                 | q_first | q_acc |
+/// |-------|---------------------|---------|-------|
+/// | b_0   | b_0                 |    1    |   0   |
+/// | b_1   | 2·acc_0 + b_1       |    0    |   1   |
+/// | ...   | ...                 |    0    |   1   |
+/// | b_n-1 | value               |    0    |   1   |
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::running_sum::decompose_windows;
+
+#[derive(Debug, Clone)]
+pub struct BinaryRowRangeConfig<F: PrimeField> {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    q_first: Selector,
+    q_acc: Selector,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BinaryRowRangeConfig<F> {
+    /// 配置布尔约束和累加约束
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        bit: Column<Advice>,
+        acc: Column<Advice>,
+    ) -> Self {
+        let q_first = meta.selector();
+        let q_acc = meta.selector();
+
+        meta.enable_equality(acc);
+
+        // 每一行的位都是0或1
+        meta.create_gate("binary_row_bit", |meta| {
+            let q = meta.query_selector(q_first) + meta.query_selector(q_acc);
+            let bit = meta.query_advice(bit, Rotation::cur());
+
+            vec![q * bit.clone() * (bit - Expression::Constant(F::ONE))]
+        });
+
+        // 第一行：acc = bit
+        meta.create_gate("binary_row_first", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            vec![q_first * (acc - bit)]
+        });
+
+        // 之后每一行：acc = 2 · acc_prev + bit
+        meta.create_gate("binary_row_acc", |meta| {
+            let q_acc = meta.query_selector(q_acc);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+
+            vec![q_acc * (acc - (acc_prev * Expression::Constant(F::from(2)) + bit))]
+        });
+
+        BinaryRowRangeConfig {
+            bit,
+            acc,
+            q_first,
+            q_acc,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 分配新值并检查它小于 2^num_bits，返回最后一行的累加器（即该值）
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "binary row range check",
+            |mut region| self.assign_bits(&mut region, value, num_bits),
+        )
+    }
+
+    /// 对已分配的cell做范围检查，cell与最后一行的累加器copy约束
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        element: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "binary row range check of assigned cell",
+            |mut region| {
+                let value = element.value().copied();
+                let acc = self.assign_bits(&mut region, value, num_bits)?;
+                region.constrain_equal(element.cell(), acc.cell())?;
+                Ok(acc)
+            },
+        )
+    }
+
+    /// 从最高位开始每行分配一位和累加器
+    ///
+    /// 最后一行的累加器直接分配为 `value`，位无法重建该值时累加约束失败
+    ///
+    /// 要求 0 < num_bits < F::NUM_BITS，否则返回 `Error::Synthesis`
+    fn assign_bits(
+        &self,
+        region: &mut Region<'_, F>,
+        value: Value<F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // 累加结果必须小于域的模数，否则 2^num_bits 以上的值可能回绕
+        if num_bits == 0 || num_bits >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        // 小端的位，反转后从最高位开始
+        let bits = value.map(|v| {
+            let mut bits = decompose_windows(&v, 1, num_bits);
+            bits.reverse();
+            bits
+        });
+
+        let last = num_bits - 1;
+        let mut acc = Value::known(F::ZERO);
+        let mut acc_cell = None;
+        for row in 0..num_bits {
+            if row == 0 {
+                self.q_first.enable(region, row)?;
+            } else {
+                self.q_acc.enable(region, row)?;
+            }
+
+            let bit = bits.as_ref().map(|b| F::from(b[row]));
+            region.assign_advice(|| format!("bit {row}"), self.bit, row, || bit)?;
+
+            acc = acc.zip(bit).map(|(acc, bit)| acc.double() + bit);
+            let acc_value = if row == last { value } else { acc };
+            acc_cell =
+                Some(region.assign_advice(|| format!("acc {row}"), self.acc, row, || acc_value)?);
+        }
+
+        acc_cell.ok_or(Error::Synthesis)
+    }
+}
+
+/// ==============================================
+/// 测试电路：用同一对列检查多个 `num_bits` 位的值
+/// ==============================================
+
+pub struct BinaryRowRangeCircuit<F: PrimeField> {
+    pub values: Vec<Value<F>>,
+    pub num_bits: usize,
+}
+
+impl<F: PrimeField> BinaryRowRangeCircuit<F> {
+    pub fn new(values: Vec<F>, num_bits: usize) -> Self {
+        Self {
+            values: values.into_iter().map(Value::known).collect(),
+            num_bits,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for BinaryRowRangeCircuit<F> {
+    type Config = BinaryRowRangeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    /// 行数取决于值的个数和位宽，两者都保留
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            num_bits: self.num_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let bit = meta.advice_column();
+        let acc = meta.advice_column();
+        BinaryRowRangeConfig::configure(meta, bit, acc)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        for (i, &value) in self.values.iter().enumerate() {
+            config.witness_check(
+                layouter.namespace(|| format!("value {i}")),
+                value,
+                self.num_bits,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    fn run(values: Vec<Fp>, num_bits: usize) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+        let circuit = BinaryRowRangeCircuit::new(values, num_bits);
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        MockProver::run(k, &circuit, vec![]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_binary_row_range_check() {
+        let two = Fp::from(2);
+        for num_bits in [1, 8, 32, 64, 200] {
+            let max = two.pow_vartime([num_bits as u64]) - Fp::one();
+            let overflow = two.pow_vartime([num_bits as u64]);
+
+            assert_eq!(run(vec![Fp::zero(), max, Fp::from(1)], num_bits), Ok(()));
+            assert!(run(vec![overflow], num_bits).is_err());
+            assert!(run(vec![Fp::zero(), -Fp::one()], num_bits).is_err());
+        }
+
+        // 两列、每位一行：值的个数和位宽只影响行数
+        let circuit = BinaryRowRangeCircuit::new(vec![Fp::from(0xFFFF_FFFF); 16], 32);
+        let stats = CircuitStats::measure(&circuit).unwrap();
+        assert_eq!(stats.advice_columns, 2);
+        assert_eq!(stats.gates, 3);
+        assert_eq!(stats.region_rows, 16 * 32);
+
+        println!("按行二进制范围检查测试通过！");
+    }
+
+    #[test]
+    fn test_invalid_bit_widths_are_synthesis_errors() {
+        for num_bits in [0, Fp::NUM_BITS as usize] {
+            let circuit = BinaryRowRangeCircuit::new(vec![Fp::zero()], num_bits);
+            assert!(matches!(
+                MockProver::run(9, &circuit, vec![]),
+                Err(Error::Synthesis)
+            ));
+        }
+
+        println!("非法位宽返回Synthesis错误测试通过！");
+    }
+}
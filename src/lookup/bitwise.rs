@@ -0,0 +1,458 @@
+/// ==============================================
+/// 多列lookup table与按字节的位运算
+/// ==============================================
+///
+/// `LookUpTable` 只有一列连续整数。这里的 `TupleTable` 由N个 `TableColumn` 组成，
+/// 每一行是一个元组，lookup同时查询整行。`BitwiseTable` 用它存放
+/// `(x, y, x op y)`，op为AND、OR或XOR，x、y取遍 `0..2^bits`。
+///
+/// | x | y | z | q_lookup |     | table_x | table_y | table_z   |
+/// |---|---|---|----------|     |---------|---------|-----------|
+/// | x | y | z |    1     |     |    0    |    0    | 0 op 0    |
+/// |   |   |   |    0     |     |   ...   |   ...   |   ...     |
+///
+/// lookup同时保证x、y在 `0..2^bits` 内，可以直接作用于 `BitDecompositionConfig`
+/// 输出的字节，也可以对未检查过的值使用。
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::table::low_u64;
+
+/// N列的元组lookup table
+///
+/// selector关闭的行输入为全0元组，所以加载的行中必须包含全0元组
+#[derive(Debug, Clone)]
+pub struct TupleTable<F: PrimeField, const N: usize> {
+    /// lookup参数中引用的table列
+    pub columns: [TableColumn; N],
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const N: usize> TupleTable<F, N> {
+    /// 分配N个新的table列
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            columns: [(); N].map(|_| meta.lookup_table_column()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// lookup参数：`selector · input_i` 对应第i列
+    pub fn lookup(
+        &self,
+        selector: Expression<F>,
+        inputs: [Expression<F>; N],
+    ) -> Vec<(Expression<F>, TableColumn)> {
+        inputs
+            .into_iter()
+            .zip(self.columns)
+            .map(|(input, column)| (selector.clone() * input, column))
+            .collect()
+    }
+
+    /// 按行填充table（每个电路只能加载一次）
+    ///
+    /// 行中没有全0元组时返回 `Error::Synthesis`
+    pub fn load(&self, layouter: &mut impl Layouter<F>, rows: &[[F; N]]) -> Result<(), Error> {
+        if !rows.iter().any(|row| row.iter().all(|v| *v == F::ZERO)) {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_table(
+            || "load tuple lookup table",
+            |mut table| {
+                for (offset, row) in rows.iter().enumerate() {
+                    for (&column, &value) in self.columns.iter().zip(row.iter()) {
+                        table.assign_cell(
+                            || "tuple table cell",
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// 按位运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitwiseOp {
+    pub fn apply(self, x: u64, y: u64) -> u64 {
+        match self {
+            BitwiseOp::And => x & y,
+            BitwiseOp::Or => x | y,
+            BitwiseOp::Xor => x ^ y,
+        }
+    }
+}
+
+/// 存放 `(x, y, x op y)` 的三列table，x、y取遍 `0..2^bits`
+#[derive(Debug, Clone)]
+pub struct BitwiseTable<F: PrimeField> {
+    pub table: TupleTable<F, 3>,
+    op: BitwiseOp,
+    bits: usize,
+}
+
+impl<F: PrimeField> BitwiseTable<F> {
+    /// `bits` 位操作数的table，共 `2^(2·bits)` 行
+    pub fn configure(meta: &mut ConstraintSystem<F>, op: BitwiseOp, bits: usize) -> Self {
+        assert!(
+            bits > 0 && bits <= 8,
+            "bitwise table needs 0 < bits <= 8, got {bits}"
+        );
+        Self {
+            table: TupleTable::configure(meta),
+            op,
+            bits,
+        }
+    }
+
+    /// 按字节运算的table（65536行）
+    pub fn bytes(meta: &mut ConstraintSystem<F>, op: BitwiseOp) -> Self {
+        Self::configure(meta, op, 8)
+    }
+
+    pub fn op(&self) -> BitwiseOp {
+        self.op
+    }
+
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// 生成并加载所有 `(x, y, x op y)`
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let size = 1u64 << self.bits;
+        let rows: Vec<[F; 3]> = (0..size)
+            .flat_map(|x| (0..size).map(move |y| (x, y)))
+            .map(|(x, y)| [F::from(x), F::from(y), F::from(self.op.apply(x, y))])
+            .collect();
+        self.table.load(layouter, &rows)
+    }
+}
+
+/// 检查一行 `(x, y, z)` 满足 `z = x op y`
+#[derive(Debug, Clone)]
+pub struct BitwiseConfig<F: PrimeField> {
+    x: Column<Advice>,
+    y: Column<Advice>,
+    z: Column<Advice>,
+    q_lookup: Selector,
+    table: BitwiseTable<F>,
+}
+
+impl<F: PrimeField> BitwiseConfig<F> {
+    /// advice列依次为 [x, y, z]，多个op可以共用同一组advice列
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        table: BitwiseTable<F>,
+    ) -> Self {
+        let [x, y, z] = advice;
+        let q_lookup = meta.complex_selector();
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let inputs = [x, y, z].map(|column| meta.query_advice(column, Rotation::cur()));
+            table.table.lookup(q, inputs)
+        });
+
+        BitwiseConfig {
+            x,
+            y,
+            z,
+            q_lookup,
+            table,
+        }
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.table.load(layouter)
+    }
+
+    /// 计算 `x op y`，x、y通过copy约束复制进来
+    pub fn apply(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        y: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let op = self.table.op;
+        let z = x
+            .value()
+            .zip(y.value())
+            .map(|(x, y)| F::from(op.apply(low_u64(x), low_u64(y))));
+
+        layouter.assign_region(
+            || format!("bitwise {op:?}"),
+            |mut region| self.assign_row(&mut region, x, y, z),
+        )
+    }
+
+    /// 对两组字节逐个运算
+    pub fn apply_bytes<const N: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: &[AssignedCell<F, F>; N],
+        y: &[AssignedCell<F, F>; N],
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let mut out = Vec::with_capacity(N);
+        for (i, (x, y)) in x.iter().zip(y.iter()).enumerate() {
+            out.push(self.apply(layouter.namespace(|| format!("byte {i}")), x, y)?);
+        }
+        Ok(out.try_into().unwrap())
+    }
+
+    /// 在第0行复制x、y并分配z
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        x: &AssignedCell<F, F>,
+        y: &AssignedCell<F, F>,
+        z: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.q_lookup.enable(region, 0)?;
+        x.copy_advice(|| "x", region, self.x, 0)?;
+        y.copy_advice(|| "y", region, self.y, 0)?;
+        region.assign_advice(|| "z", self.z, 0, || z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lookup::{
+            large_range_analysis::BitDecompositionConfig,
+            table::{LookUpTable, TableRange},
+        },
+        stats::CircuitStats,
+    };
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, pasta::Fp};
+
+    /// 两个32位字按字节分解，再逐字节计算AND和XOR并公开结果
+    #[derive(Default)]
+    struct WordBitwiseCircuit {
+        a: Value<u32>,
+        b: Value<u32>,
+    }
+
+    impl Circuit<Fp> for WordBitwiseCircuit {
+        type Config = (
+            BitDecompositionConfig<Fp>,
+            BitwiseConfig<Fp>,
+            BitwiseConfig<Fp>,
+            Column<Instance>,
+        );
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let value = meta.advice_column();
+            let bytes = [(); 4].map(|_| meta.advice_column());
+            let byte_table = LookUpTable::configure(meta, TableRange::up_to(256));
+            let words = BitDecompositionConfig::configure(meta, value, bytes, byte_table);
+
+            // AND和XOR共用同一组advice列
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let and_table = BitwiseTable::bytes(meta, BitwiseOp::And);
+            let xor_table = BitwiseTable::bytes(meta, BitwiseOp::Xor);
+            let and = BitwiseConfig::configure(meta, advice, and_table);
+            let xor = BitwiseConfig::configure(meta, advice, xor_table);
+
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+
+            (words, and, xor, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (words, and, xor, instance): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            words.load_byte_table(&mut layouter)?;
+            and.load_table(&mut layouter)?;
+            xor.load_table(&mut layouter)?;
+
+            let (_, a) = words.decompose(layouter.namespace(|| "a"), self.a)?;
+            let (_, b) = words.decompose(layouter.namespace(|| "b"), self.b)?;
+
+            let a_and_b = and.apply_bytes(layouter.namespace(|| "a & b"), &a, &b)?;
+            let a_xor_b = xor.apply_bytes(layouter.namespace(|| "a ^ b"), &a, &b)?;
+            for (row, byte) in a_and_b.iter().chain(a_xor_b.iter()).enumerate() {
+                layouter.constrain_instance(byte.cell(), instance, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn bytes_of(word: u32) -> Vec<Fp> {
+        word.to_le_bytes()
+            .iter()
+            .map(|&b| Fp::from(b as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_bytewise_and_xor() {
+        let (a, b) = (0x1234_ABCD_u32, 0xFF00_0F0F_u32);
+        let circuit = WordBitwiseCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+
+        let public = [bytes_of(a & b), bytes_of(a ^ b)].concat();
+        let prover = MockProver::run(k, &circuit, vec![public]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // 把OR当作AND的结果公开，验证失败
+        let public = [bytes_of(a | b), bytes_of(a ^ b)].concat();
+        let prover = MockProver::run(k, &circuit, vec![public]).unwrap();
+        assert!(prover.verify().is_err());
+
+        println!("按字节AND/XOR测试通过！");
+    }
+
+    /// 在4位XOR表上直接分配 `(x, y, z)`，z可以伪造
+    #[derive(Default)]
+    struct XorRowCircuit {
+        x: Value<u64>,
+        y: Value<u64>,
+        z: Value<u64>,
+    }
+
+    impl Circuit<Fp> for XorRowCircuit {
+        type Config = BitwiseConfig<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let table = BitwiseTable::configure(meta, BitwiseOp::Xor, 4);
+            BitwiseConfig::configure(meta, advice, table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load_table(&mut layouter)?;
+            let (x, y) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let x = region.assign_advice(|| "x", config.x, 0, || self.x.map(Fp::from))?;
+                    let y = region.assign_advice(|| "y", config.y, 0, || self.y.map(Fp::from))?;
+                    Ok((x, y))
+                },
+            )?;
+            layouter.assign_region(
+                || "xor row",
+                |mut region| config.assign_row(&mut region, &x, &y, self.z.map(Fp::from)),
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tuple_lookup_rejects_bad_rows() {
+        let run = |x, y, z| {
+            let circuit = XorRowCircuit {
+                x: Value::known(x),
+                y: Value::known(y),
+                z: Value::known(z),
+            };
+            let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+            MockProver::run(k, &circuit, vec![]).unwrap().verify()
+        };
+
+        assert_eq!(run(0b1010, 0b0110, 0b1100), Ok(()));
+        assert_eq!(run(15, 15, 0), Ok(()));
+        // 结果错误
+        assert!(run(0b1010, 0b0110, 0b1110).is_err());
+        // 操作数超出4位
+        assert!(run(16, 0, 16).is_err());
+
+        println!("元组lookup测试通过！");
+    }
+
+    /// 只加载给定的行，不做任何lookup
+    struct TupleTableCircuit {
+        rows: Vec<[Fp; 2]>,
+    }
+
+    impl Circuit<Fp> for TupleTableCircuit {
+        type Config = TupleTable<Fp, 2>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                rows: self.rows.clone(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            TupleTable::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load(&mut layouter, &self.rows)
+        }
+    }
+
+    #[test]
+    fn test_tuple_table_requires_zero_row() {
+        let row = |x: u64, y: u64| [Fp::from(x), Fp::from(y)];
+
+        let circuit = TupleTableCircuit {
+            rows: vec![row(0, 0), row(1, 2)],
+        };
+        assert_eq!(
+            MockProver::run(4, &circuit, vec![]).unwrap().verify(),
+            Ok(())
+        );
+
+        // 缺少全0元组时返回错误而不是panic
+        let circuit = TupleTableCircuit {
+            rows: vec![row(0, 1), row(1, 2)],
+        };
+        assert!(matches!(
+            MockProver::run(4, &circuit, vec![]),
+            Err(Error::Synthesis)
+        ));
+
+        println!("元组table全0行检查测试通过！");
+    }
+}
@@ -0,0 +1,396 @@
+/// ==============================================
+/// 全域元素的规范位分解
+/// ==============================================
+///
+/// 把任意 `F: PrimeField` 元素拆成 `F::NUM_BITS` 个二进制位，并约束这些位表示的整数
+/// 小于模数。没有这个约束时，p + x 的位分解与 x 的位分解都能通过累加约束（两者在域中相等），
+/// 对位做哈希或比较就不再唯一。
+///
+/// 从最高位开始逐行比较位串 b 与 p - 1 的位串 m：
+///   eq_i = eq_{i-1} · (1 - (b_i - m_i)²)        前缀完全相等
+///   lt_i = lt_{i-1} + eq_{i-1} · m_i · (1 - b_i) 前缀已经严格小于
+/// 最后一行要求 lt + eq = 1，即 b ≤ p - 1 < p。
+///
+/// | bit | acc               | eq   | lt   | m (fixed) | q_first | q_step | q_last |
+/// |-----|-------------------|------|------|-----------|---------|--------|--------|
+/// | b_0 | b_0               | eq_0 | lt_0 | m_0       |    1    |   0    |   0    |
+/// | b_1 | 2·acc_0 + b_1     | eq_1 | lt_1 | m_1       |    0    |   1    |   0    |
+/// | ... | ...               | ...  | ...  | ...       |    0    |   1    |   0    |
+/// | b_n | value             | eq_n | lt_n | m_n       |    0    |   1    |   1    |
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::running_sum::decompose_windows;
+
+#[derive(Debug, Clone)]
+pub struct CanonicalDecompositionConfig<F: PrimeField> {
+    bit: Column<Advice>,
+    acc: Column<Advice>,
+    eq: Column<Advice>,
+    lt: Column<Advice>,
+    /// p - 1 的二进制位（最高位在第0行）
+    modulus: Column<Fixed>,
+    q_first: Selector,
+    q_step: Selector,
+    q_last: Selector,
+    _marker: PhantomData<F>,
+}
+
+/// 规范分解的结果
+#[derive(Debug, Clone)]
+pub struct CanonicalBits<F: PrimeField> {
+    /// 被分解的值
+    pub value: AssignedCell<F, F>,
+    /// 小端顺序的 `F::NUM_BITS` 个位
+    pub bits: Vec<AssignedCell<F, F>>,
+}
+
+impl<F: PrimeField> CanonicalDecompositionConfig<F> {
+    /// advice列依次为 [bit, acc, eq, lt]，`modulus` 存放模数的位
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        modulus: Column<Fixed>,
+    ) -> Self {
+        let [bit, acc, eq, lt] = advice;
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+        let q_last = meta.selector();
+
+        meta.enable_equality(bit);
+        meta.enable_equality(acc);
+
+        let one = || Expression::Constant(F::ONE);
+
+        // 每一行的位都是0或1
+        meta.create_gate("canonical_bit", |meta| {
+            let q = meta.query_selector(q_first) + meta.query_selector(q_step);
+            let b = meta.query_advice(bit, Rotation::cur());
+
+            vec![q * b.clone() * (b - one())]
+        });
+
+        // 第一行：没有前缀
+        meta.create_gate("canonical_first", |meta| {
+            let q = meta.query_selector(q_first);
+            let b = meta.query_advice(bit, Rotation::cur());
+            let m = meta.query_fixed(modulus);
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let lt = meta.query_advice(lt, Rotation::cur());
+
+            let diff = b.clone() - m.clone();
+            vec![
+                q.clone() * (acc - b.clone()),
+                q.clone() * (eq - (one() - diff.clone() * diff)),
+                q * (lt - m * (one() - b)),
+            ]
+        });
+
+        // 之后每一行：累加并更新比较状态
+        meta.create_gate("canonical_step", |meta| {
+            let q = meta.query_selector(q_step);
+            let b = meta.query_advice(bit, Rotation::cur());
+            let m = meta.query_fixed(modulus);
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let eq_prev = meta.query_advice(eq, Rotation::prev());
+            let lt = meta.query_advice(lt, Rotation::cur());
+            let lt_prev = meta.query_advice(lt, Rotation::prev());
+
+            let diff = b.clone() - m.clone();
+            vec![
+                q.clone() * (acc - (acc_prev * Expression::Constant(F::from(2)) + b.clone())),
+                q.clone() * (eq - eq_prev.clone() * (one() - diff.clone() * diff)),
+                q * (lt - (lt_prev + eq_prev * m * (one() - b))),
+            ]
+        });
+
+        // 最后一行：b ≤ p - 1
+        meta.create_gate("canonical_bound", |meta| {
+            let q = meta.query_selector(q_last);
+            let eq = meta.query_advice(eq, Rotation::cur());
+            let lt = meta.query_advice(lt, Rotation::cur());
+
+            vec![q * (lt + eq - one())]
+        });
+
+        CanonicalDecompositionConfig {
+            bit,
+            acc,
+            eq,
+            lt,
+            modulus,
+            q_first,
+            q_step,
+            q_last,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 分配新值并规范分解
+    pub fn witness_decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<CanonicalBits<F>, Error> {
+        layouter.assign_region(
+            || "canonical decomposition",
+            |mut region| {
+                let bits = value.map(|v| decompose_windows(&v, 1, F::NUM_BITS as usize));
+                self.assign_rows(&mut region, value, bits)
+            },
+        )
+    }
+
+    /// 规范分解已分配的cell，cell与最后一行的累加器copy约束
+    pub fn copy_decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        element: &AssignedCell<F, F>,
+    ) -> Result<CanonicalBits<F>, Error> {
+        layouter.assign_region(
+            || "canonical decomposition of assigned cell",
+            |mut region| {
+                let value = element.value().copied();
+                let bits = value.map(|v| decompose_windows(&v, 1, F::NUM_BITS as usize));
+                let decomposition = self.assign_rows(&mut region, value, bits)?;
+                region.constrain_equal(element.cell(), decomposition.value.cell())?;
+                Ok(decomposition)
+            },
+        )
+    }
+
+    /// 按给定的小端位串逐行分配，最后一行的累加器直接分配为 `value`
+    fn assign_rows(
+        &self,
+        region: &mut Region<'_, F>,
+        value: Value<F>,
+        bits_le: Value<Vec<u64>>,
+    ) -> Result<CanonicalBits<F>, Error> {
+        let num_bits = F::NUM_BITS as usize;
+        let last = num_bits - 1;
+
+        // p - 1 是最大的规范值，从最高位开始比较
+        let mut modulus = decompose_windows(&-F::ONE, 1, num_bits);
+        modulus.reverse();
+        let bits = bits_le.map(|mut bits| {
+            bits.reverse();
+            bits
+        });
+
+        let mut acc = Value::known(F::ZERO);
+        let mut eq = Value::known(true);
+        let mut lt = Value::known(false);
+        let mut bit_cells = Vec::with_capacity(num_bits);
+        let mut value_cell = None;
+
+        for (row, &m) in modulus.iter().enumerate() {
+            if row == 0 {
+                self.q_first.enable(region, row)?;
+            } else {
+                self.q_step.enable(region, row)?;
+            }
+            if row == last {
+                self.q_last.enable(region, row)?;
+            }
+
+            region.assign_fixed(
+                || format!("modulus bit {row}"),
+                self.modulus,
+                row,
+                || Value::known(F::from(m)),
+            )?;
+
+            let b = bits.as_ref().map(|bits| bits[row]);
+            let bit = b.map(F::from);
+            bit_cells.push(region.assign_advice(|| format!("bit {row}"), self.bit, row, || bit)?);
+
+            acc = acc.zip(bit).map(|(acc, bit)| acc.double() + bit);
+            let acc_value = if row == last { value } else { acc };
+            let acc_cell =
+                region.assign_advice(|| format!("acc {row}"), self.acc, row, || acc_value)?;
+
+            // 先用上一行的eq更新lt，再更新eq
+            lt = lt
+                .zip(eq)
+                .zip(b)
+                .map(|((lt, eq), b)| lt || (eq && m == 1 && b == 0));
+            eq = eq.zip(b).map(|(eq, b)| eq && b == m);
+            region.assign_advice(
+                || format!("eq {row}"),
+                self.eq,
+                row,
+                || eq.map(|eq| F::from(eq as u64)),
+            )?;
+            region.assign_advice(
+                || format!("lt {row}"),
+                self.lt,
+                row,
+                || lt.map(|lt| F::from(lt as u64)),
+            )?;
+
+            if row == last {
+                value_cell = Some(acc_cell);
+            }
+        }
+
+        bit_cells.reverse();
+        Ok(CanonicalBits {
+            value: value_cell.expect("at least one bit"),
+            bits: bit_cells,
+        })
+    }
+}
+
+/// ==============================================
+/// 测试电路：规范分解一个域元素
+/// ==============================================
+
+#[derive(Default)]
+pub struct CanonicalDecompositionCircuit<F: PrimeField> {
+    pub value: Value<F>,
+}
+
+impl<F: PrimeField> CanonicalDecompositionCircuit<F> {
+    pub fn new(value: F) -> Self {
+        Self {
+            value: Value::known(value),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for CanonicalDecompositionCircuit<F> {
+    type Config = CanonicalDecompositionConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let modulus = meta.fixed_column();
+        CanonicalDecompositionConfig::configure(meta, advice, modulus)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.witness_decompose(layouter.namespace(|| "decompose"), self.value)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::{Fp, Fq},
+    };
+
+    /// 用指定的位串分解value，用来构造非规范的witness
+    #[derive(Default)]
+    struct ForgedCircuit<F: PrimeField> {
+        value: Value<F>,
+        bits_le: Value<Vec<u64>>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for ForgedCircuit<F> {
+        type Config = CanonicalDecompositionConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            CanonicalDecompositionCircuit::<F>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "forged decomposition",
+                |mut region| config.assign_rows(&mut region, self.value, self.bits_le.clone()),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn run<F: PrimeField + Ord, C: Circuit<F>>(circuit: &C) -> Result<(), Vec<VerifyFailure>> {
+        let k = CircuitStats::measure(circuit).unwrap().minimal_k;
+        MockProver::run(k, circuit, vec![]).unwrap().verify()
+    }
+
+    fn check_field<F: PrimeField + Ord>() {
+        let num_bits = F::NUM_BITS as usize;
+
+        // 0、1、p - 1 以及一个接近模数的大值都能规范分解
+        let big = -F::from(0x1234_5678_9abc_def0);
+        for value in [F::ZERO, F::ONE, -F::ONE, big] {
+            let circuit = CanonicalDecompositionCircuit::new(value);
+            assert_eq!(run(&circuit), Ok(()));
+        }
+
+        // p + 5 的位串在域中同样等于5，但不是规范的
+        let mut forged = decompose_windows(&-F::ONE, 1, num_bits);
+        let mut carry = 6;
+        for bit in forged.iter_mut() {
+            let sum = *bit + carry;
+            *bit = sum & 1;
+            carry = sum >> 1;
+        }
+        assert_eq!(carry, 0, "p + 5 must fit in NUM_BITS bits");
+
+        let circuit = ForgedCircuit {
+            value: Value::known(F::from(5)),
+            bits_le: Value::known(forged),
+        };
+        let failures = run(&circuit).unwrap_err();
+        assert!(
+            failures
+                .iter()
+                .all(|f| format!("{f}").contains("canonical_bound"))
+        );
+
+        // 规范的位串本身可以通过
+        let circuit = ForgedCircuit {
+            value: Value::known(F::from(5)),
+            bits_le: Value::known(decompose_windows(&F::from(5), 1, num_bits)),
+        };
+        assert_eq!(run(&circuit), Ok(()));
+    }
+
+    #[test]
+    fn test_canonical_decomposition_fp() {
+        check_field::<Fp>();
+        println!("Fp规范分解测试通过！");
+    }
+
+    #[test]
+    fn test_canonical_decomposition_fq() {
+        check_field::<Fq>();
+        println!("Fq规范分解测试通过！");
+    }
+}
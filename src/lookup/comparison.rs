@@ -0,0 +1,576 @@
+/// ==============================================
+/// 比较Chip：a < b、a <= b 以及返回布尔值的比较
+/// ==============================================
+///
+/// 操作数都是 N = 8·BYTES 位的值：比较方法只接受 `witness_bits` / `assert_bits`
+/// 返回的 `Bounded`，公开阈值在比较前同样做范围检查。
+/// 把差值分解为BYTES个字节并逐个查0..256的字节表：
+///   a <  b  ⟺  b - a - 1 ∈ [0, 2^N)
+///   a <= b  ⟺  b - a     ∈ [0, 2^N)
+///   lt = [a < b]：a - b + lt·2^N ∈ [0, 2^N)，lt ∈ {0, 1}
+/// 最后一种情况下lt取错值时差值不是N位数（N + 1 位小于域的位数），无法通过字节检查。
+///
+/// | a/bound | b | lt | byte0 | ... | byte(BYTES-1)  | 选择器  |
+/// |---------|---|----|-------|-----|----------------|---------|
+/// |    a    |   |    |  a的字节分解                   | s_range |
+/// |  bound  |   |    |  bound的字节分解（instance复制）| s_range |
+/// |    a    | b |    |  b - a - 1 的字节分解          | s_lt    |
+/// |    a    | b |    |  b - a 的字节分解              | s_le    |
+/// |    a    | b | lt |  a - b + lt·2^N 的字节分解     | s_cmp   |
+///
+/// 没有复用 `BitDecompositionConfig::range_check_cell`：
+/// - 它固定为4个字节（32位），这里的位宽由BYTES决定；
+/// - 它只能检查已分配的cell，差值要先由额外的门分配到一行，再复制到分解region。
+///   这里的门直接把 a、b 的差值表达式与同一行的字节比较，每次比较只占一行。
+/// 两者共享0..256字节表（可以来自同一个 `TableRegistry`）和 `decompose_windows`。
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::{
+    running_sum::decompose_windows,
+    table::{LookUpTable, TableRange, two_pow},
+};
+use crate::basic::boolean::Bool;
+
+/// 已检查为 N = 8·BYTES 位的cell，只能由 `ComparisonConfig` 的范围检查得到
+#[derive(Debug, Clone)]
+pub struct Bounded<F: PrimeField, const BYTES: usize>(AssignedCell<F, F>);
+
+impl<F: PrimeField, const BYTES: usize> Bounded<F, BYTES> {
+    pub fn cell(&self) -> &AssignedCell<F, F> {
+        &self.0
+    }
+
+    pub fn into_cell(self) -> AssignedCell<F, F> {
+        self.0
+    }
+
+    pub fn value(&self) -> Value<&F> {
+        self.0.value()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ComparisonConfig<F: PrimeField, const BYTES: usize> {
+    advice: [Column<Advice>; 3], // [a, b, lt]
+    bytes: [Column<Advice>; BYTES],
+    byte_table: LookUpTable<F>,
+    instance: Column<Instance>,
+    s_range: Selector,
+    s_lt: Selector,
+    s_le: Selector,
+    s_cmp: Selector,
+    s_lookup: Selector,
+}
+
+impl<F: PrimeField, const BYTES: usize> ComparisonConfig<F, BYTES> {
+    /// `byte_table` 可以来自 `TableRegistry` 与其他芯片共享，
+    /// `instance` 中保存公开阈值
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        bytes: [Column<Advice>; BYTES],
+        byte_table: LookUpTable<F>,
+        instance: Column<Instance>,
+    ) -> Self {
+        assert_eq!(
+            byte_table.range(),
+            TableRange::up_to(256),
+            "comparison needs a 0..256 byte table"
+        );
+        assert!(
+            BYTES > 0 && 8 * BYTES + 1 < F::NUM_BITS as usize,
+            "{}-bit operands do not fit the field",
+            8 * BYTES
+        );
+
+        let s_range = meta.selector();
+        let s_lt = meta.selector();
+        let s_le = meta.selector();
+        let s_cmp = meta.selector();
+        let s_lookup = meta.complex_selector();
+
+        // 启用equality约束
+        meta.enable_equality(instance);
+        for c in &advice {
+            meta.enable_equality(*c);
+        }
+
+        let two_pow_n = two_pow::<F>(8 * BYTES);
+
+        // 字节重组：byte0 + byte1·256 + ...
+        let recompose = |meta: &mut VirtualCells<'_, F>| {
+            bytes
+                .iter()
+                .rev()
+                .fold(Expression::Constant(F::ZERO), |acc, &col| {
+                    acc * Expression::Constant(F::from(256))
+                        + meta.query_advice(col, Rotation::cur())
+                })
+        };
+
+        // 操作数范围检查：a = Σ byte_i·256^i
+        meta.create_gate("comparison_range", |meta| {
+            let s = meta.query_selector(s_range);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            vec![s * (a - recompose(meta))]
+        });
+
+        // a < b：b - a - 1 = Σ byte_i·256^i
+        meta.create_gate("comparison_lt", |meta| {
+            let s = meta.query_selector(s_lt);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            vec![s * (b - a - Expression::Constant(F::ONE) - recompose(meta))]
+        });
+
+        // a <= b：b - a = Σ byte_i·256^i
+        meta.create_gate("comparison_le", |meta| {
+            let s = meta.query_selector(s_le);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            vec![s * (b - a - recompose(meta))]
+        });
+
+        // lt = [a < b]：a - b + lt·2^N = Σ byte_i·256^i，lt·(1 - lt) = 0
+        meta.create_gate("comparison_cmp", |meta| {
+            let s = meta.query_selector(s_cmp);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let lt = meta.query_advice(advice[2], Rotation::cur());
+            vec![
+                s.clone()
+                    * (a - b + lt.clone() * Expression::Constant(two_pow_n) - recompose(meta)),
+                s * lt.clone() * (Expression::Constant(F::ONE) - lt),
+            ]
+        });
+
+        // 每个字节在[0,255]范围内
+        for &col in bytes.iter() {
+            meta.lookup(|meta| {
+                let s_lookup = meta.query_selector(s_lookup);
+                let byte = meta.query_advice(col, Rotation::cur());
+                vec![(byte_table.input(s_lookup, byte), byte_table.table)]
+            });
+        }
+
+        ComparisonConfig {
+            advice,
+            bytes,
+            byte_table,
+            instance,
+            s_range,
+            s_lt,
+            s_le,
+            s_cmp,
+            s_lookup,
+        }
+    }
+
+    /// 加载256个值的字节表，只用于这个芯片独占的table
+    ///
+    /// `byte_table` 来自 `TableRegistry` 时由 `TableLoader` 加载，
+    /// 不要再调用这个方法，否则同一列会被加载两次
+    pub fn load_byte_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.byte_table.load(layouter)
+    }
+
+    /// 分配N位操作数
+    pub fn witness_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<Bounded<F, BYTES>, Error> {
+        layouter.assign_region(
+            || "comparison operand",
+            |mut region| {
+                let cell = region.assign_advice(|| "a", self.advice[0], 0, || value)?;
+                self.assign_range(&mut region, cell)
+            },
+        )
+    }
+
+    /// 检查已分配的cell是N位数（例如其他芯片的输出）
+    pub fn assert_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<Bounded<F, BYTES>, Error> {
+        layouter.assign_region(
+            || "comparison operand of assigned cell",
+            |mut region| {
+                let copied = cell.copy_advice(|| "a", &mut region, self.advice[0], 0)?;
+                self.assign_range(&mut region, copied)
+            },
+        )
+    }
+
+    /// 约束 a < b
+    pub fn assert_less_than(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        b: &Bounded<F, BYTES>,
+    ) -> Result<(), Error> {
+        self.assert_order(layouter, a, b, true)
+    }
+
+    /// 约束 a <= b
+    pub fn assert_less_equal(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        b: &Bounded<F, BYTES>,
+    ) -> Result<(), Error> {
+        self.assert_order(layouter, a, b, false)
+    }
+
+    /// a < b 时返回1，否则返回0
+    pub fn less_than(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        b: &Bounded<F, BYTES>,
+    ) -> Result<Bool<F>, Error> {
+        self.compare(layouter, a, b)
+    }
+
+    /// 约束 a < instance[row]，公开阈值不是N位数时验证失败
+    pub fn assert_less_than_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        row: usize,
+    ) -> Result<(), Error> {
+        let bound = self.public_bits(layouter.namespace(|| "bound"), row)?;
+        self.assert_order(layouter, a, &bound, true)
+    }
+
+    /// 约束 a <= instance[row]，公开阈值不是N位数时验证失败
+    pub fn assert_less_equal_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        row: usize,
+    ) -> Result<(), Error> {
+        let bound = self.public_bits(layouter.namespace(|| "bound"), row)?;
+        self.assert_order(layouter, a, &bound, false)
+    }
+
+    /// a < instance[row] 时返回1，否则返回0，公开阈值不是N位数时验证失败
+    pub fn less_than_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        row: usize,
+    ) -> Result<Bool<F>, Error> {
+        let bound = self.public_bits(layouter.namespace(|| "bound"), row)?;
+        self.compare(layouter, a, &bound)
+    }
+
+    /// 从instance列复制公开阈值并检查它是N位数
+    fn public_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<Bounded<F, BYTES>, Error> {
+        layouter.assign_region(
+            || "public bound",
+            |mut region| {
+                let cell = region.assign_advice_from_instance(
+                    || "bound",
+                    self.instance,
+                    row,
+                    self.advice[0],
+                    0,
+                )?;
+                self.assign_range(&mut region, cell)
+            },
+        )
+    }
+
+    /// 对第0行已分配的操作数启用范围检查
+    fn assign_range(
+        &self,
+        region: &mut Region<'_, F>,
+        cell: AssignedCell<F, F>,
+    ) -> Result<Bounded<F, BYTES>, Error> {
+        self.s_range.enable(region, 0)?;
+        self.assign_bytes(region, cell.value().copied())?;
+        Ok(Bounded(cell))
+    }
+
+    /// 在第0行复制a和b
+    fn assign_operands(
+        &self,
+        region: &mut Region<'_, F>,
+        a: &Bounded<F, BYTES>,
+        b: &Bounded<F, BYTES>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let a = a.cell().copy_advice(|| "a", region, self.advice[0], 0)?;
+        let b = b.cell().copy_advice(|| "b", region, self.advice[1], 0)?;
+        Ok((a, b))
+    }
+
+    /// 约束 a < b（strict）或 a <= b
+    fn assert_order(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        b: &Bounded<F, BYTES>,
+        strict: bool,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || if strict { "a < b" } else { "a <= b" },
+            |mut region| {
+                let (a, b) = self.assign_operands(&mut region, a, b)?;
+                let diff = if strict {
+                    self.s_lt.enable(&mut region, 0)?;
+                    b.value().zip(a.value()).map(|(b, a)| *b - *a - F::ONE)
+                } else {
+                    self.s_le.enable(&mut region, 0)?;
+                    b.value().zip(a.value()).map(|(b, a)| *b - *a)
+                };
+                self.assign_bytes(&mut region, diff)
+            },
+        )
+    }
+
+    /// 计算 [a < b]
+    fn compare(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Bounded<F, BYTES>,
+        b: &Bounded<F, BYTES>,
+    ) -> Result<Bool<F>, Error> {
+        layouter.assign_region(
+            || "[a < b]",
+            |mut region| {
+                let (a, b) = self.assign_operands(&mut region, a, b)?;
+                let lt = a
+                    .value()
+                    .zip(b.value())
+                    .map(|(a, b)| less_than_witness::<F, BYTES>(*a, *b));
+                self.assign_compare(&mut region, &a, &b, lt)
+                    .map(Bool::from_constrained)
+            },
+        )
+    }
+
+    /// 分配lt和 a - b + lt·2^N 的字节（a、b已在第0行）
+    fn assign_compare(
+        &self,
+        region: &mut Region<'_, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        lt: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.s_cmp.enable(region, 0)?;
+
+        let two_pow_n = two_pow::<F>(8 * BYTES);
+        let diff = a
+            .value()
+            .zip(b.value())
+            .zip(lt)
+            .map(|((a, b), lt)| *a - *b + lt * two_pow_n);
+        self.assign_bytes(region, diff)?;
+        region.assign_advice(|| "lt", self.advice[2], 0, || lt)
+    }
+
+    /// 启用lookup并在第0行分配value的低BYTES个字节
+    fn assign_bytes(&self, region: &mut Region<'_, F>, value: Value<F>) -> Result<(), Error> {
+        self.s_lookup.enable(region, 0)?;
+
+        let bytes = value.map(|v| decompose_windows(&v, 8, BYTES));
+        for (i, &column) in self.bytes.iter().enumerate() {
+            let byte = bytes.as_ref().map(|b| F::from(b[i]));
+            region.assign_advice(|| format!("byte{i}"), column, 0, || byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// 诚实的lt：a - b + 2^N 的第N位为0时 a < b
+fn less_than_witness<F: PrimeField, const BYTES: usize>(a: F, b: F) -> F {
+    let n = 8 * BYTES;
+    let shifted = a - b + two_pow::<F>(n);
+    if decompose_windows(&shifted, 1, n + 1)[n] == 0 {
+        F::ONE
+    } else {
+        F::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    const MAX: u64 = u32::MAX as u64;
+
+    /// 32位比较：公开 [bound, a < b, a < bound]，可选地断言 a (<|<=) b 和 a (<|<=) bound
+    #[derive(Default)]
+    struct ComparisonCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+        /// Some(true)断言 <，Some(false)断言 <=
+        strict: Option<bool>,
+        /// Some时用给定的lt代替 [a < b] 的诚实witness
+        forged_lt: Option<Fp>,
+    }
+
+    impl Circuit<Fp> for ComparisonCircuit {
+        type Config = ComparisonConfig<Fp, 4>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                strict: self.strict,
+                forged_lt: self.forged_lt,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let bytes = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let byte_table = LookUpTable::configure(meta, TableRange::up_to(256));
+            let instance = meta.instance_column();
+            ComparisonConfig::configure(meta, advice, bytes, byte_table, instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load_byte_table(&mut layouter)?;
+
+            let a = config.witness_bits(layouter.namespace(|| "a"), self.a)?;
+            let b = config.witness_bits(layouter.namespace(|| "b"), self.b)?;
+
+            let lt = match self.forged_lt {
+                None => config.less_than(layouter.namespace(|| "a < b"), &a, &b)?,
+                Some(lt) => Bool::from_constrained(layouter.assign_region(
+                    || "forged a < b",
+                    |mut region| {
+                        let (a, b) = config.assign_operands(&mut region, &a, &b)?;
+                        config.assign_compare(&mut region, &a, &b, Value::known(lt))
+                    },
+                )?),
+            };
+            let lt_public = config.less_than_public(layouter.namespace(|| "a < bound"), &a, 0)?;
+
+            match self.strict {
+                Some(true) => {
+                    config.assert_less_than(layouter.namespace(|| "assert a < b"), &a, &b)?;
+                    config.assert_less_than_public(
+                        layouter.namespace(|| "assert a < bound"),
+                        &a,
+                        0,
+                    )?;
+                }
+                Some(false) => {
+                    config.assert_less_equal(layouter.namespace(|| "assert a <= b"), &a, &b)?;
+                    config.assert_less_equal_public(
+                        layouter.namespace(|| "assert a <= bound"),
+                        &a,
+                        0,
+                    )?;
+                }
+                None => {}
+            }
+
+            layouter.constrain_instance(lt.cell().cell(), config.instance, 1)?;
+            layouter.constrain_instance(lt_public.cell().cell(), config.instance, 2)
+        }
+    }
+
+    fn run(
+        a: u64,
+        b: u64,
+        strict: Option<bool>,
+        forged_lt: Option<Fp>,
+        public: [u64; 3],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = ComparisonCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            strict,
+            forged_lt,
+        };
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        let public = public.iter().map(|&v| Fp::from(v)).collect();
+        MockProver::run(k, &circuit, vec![public]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_less_than() {
+        assert_eq!(run(3, 5, None, None, [4, 1, 1]), Ok(()));
+        assert_eq!(run(5, 5, None, None, [5, 0, 0]), Ok(()));
+        assert_eq!(run(5, 3, None, None, [6, 0, 1]), Ok(()));
+        assert_eq!(run(0, MAX, None, None, [MAX, 1, 1]), Ok(()));
+        assert_eq!(run(MAX, 0, None, None, [0, 0, 0]), Ok(()));
+        assert_eq!(run(MAX - 1, MAX, None, None, [MAX, 1, 1]), Ok(()));
+
+        // 错误的公开结果
+        assert!(run(3, 5, None, None, [4, 0, 1]).is_err());
+        assert!(run(3, 5, None, None, [4, 1, 0]).is_err());
+        // 操作数超出32位
+        assert!(run(MAX + 1, 0, None, None, [0, 0, 0]).is_err());
+        // 公开阈值超出32位：0 - 2^32 + 1·2^32 = 0 能通过差值的字节检查，
+        // 只能靠阈值自身的范围检查拒绝
+        assert!(run(0, 1, None, None, [MAX + 1, 1, 1]).is_err());
+
+        println!("比较测试通过！");
+    }
+
+    #[test]
+    fn test_assert_order() {
+        assert_eq!(run(3, 5, Some(true), None, [4, 1, 1]), Ok(()));
+        assert_eq!(run(5, 5, Some(false), None, [5, 0, 0]), Ok(()));
+        assert_eq!(run(0, MAX, Some(true), None, [MAX, 1, 1]), Ok(()));
+
+        // 相等时 a < b 不成立
+        assert!(run(5, 5, Some(true), None, [6, 0, 1]).is_err());
+        assert!(run(6, 5, Some(false), None, [7, 0, 1]).is_err());
+        // a < b 成立，但 a < bound 不成立
+        assert!(run(3, 5, Some(true), None, [3, 1, 0]).is_err());
+        assert!(run(3, 5, Some(false), None, [2, 1, 0]).is_err());
+
+        println!("顺序断言测试通过！");
+    }
+
+    #[test]
+    fn test_comparison_rejects_forged_flag() {
+        // 诚实的lt可以通过
+        assert_eq!(run(3, 5, None, Some(Fp::one()), [4, 1, 1]), Ok(()));
+        assert_eq!(run(5, 3, None, Some(Fp::zero()), [4, 0, 0]), Ok(()));
+        // 翻转lt
+        assert!(run(3, 5, None, Some(Fp::zero()), [4, 0, 1]).is_err());
+        assert!(run(5, 3, None, Some(Fp::one()), [4, 1, 0]).is_err());
+        assert!(run(5, 5, None, Some(Fp::one()), [4, 1, 0]).is_err());
+        // 非布尔lt
+        assert!(run(3, 5, None, Some(Fp::from(2)), [4, 2, 1]).is_err());
+
+        println!("伪造比较结果测试通过！");
+    }
+}
@@ -3,15 +3,25 @@
 /// ==============================================
 use halo2_proofs::{
     arithmetic::Field,
-    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
-    pasta::group::ff::PrimeField,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    pasta::{Fp, group::ff::PrimeField},
     plonk::*,
     poly::Rotation,
 };
 /// 大范围查找表的具体实现方案
 /// 1. 位分解 + 小范围Lookup：适合中等范围 (如2^24)
 /// 2. 二进制约束：适合大范围 (如2^32)
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    num::TryFromIntError,
+    time::{Duration, Instant},
+};
+
+use super::{
+    running_sum::decompose_windows,
+    table::{LookUpTable, TableRange},
+};
+use crate::{proof::pipeline, stats::CircuitStats};
 
 /// ==============================================
 /// 方案1：位分解 + 小范围Lookup
@@ -19,25 +29,31 @@ use std::marker::PhantomData;
 /// ==============================================
 
 #[derive(Debug, Clone)]
-struct BitDecompositionConfig<F: PrimeField> {
+pub struct BitDecompositionConfig<F: PrimeField> {
     // 存储原始值和分解后的字节
     value: Column<Advice>,
     bytes: [Column<Advice>; 4], // 4个8位字节
     // 小范围lookup table (0-255)
-    byte_table: TableColumn,
+    byte_table: LookUpTable<F>,
     // 选择器
     s_decomp: Selector, // 位分解约束
     s_lookup: Selector, // lookup约束
-    _marker: PhantomData<F>,
 }
 
 impl<F: PrimeField> BitDecompositionConfig<F> {
-    fn configure(
+    /// 配置位分解门和4个字节列的lookup，`byte_table` 由调用方分配，
+    /// 可以来自 `TableRegistry` 与其他芯片共享
+    pub fn configure(
         meta: &mut ConstraintSystem<F>,
         value: Column<Advice>,
         bytes: [Column<Advice>; 4],
-        byte_table: TableColumn,
+        byte_table: LookUpTable<F>,
     ) -> Self {
+        assert_eq!(
+            byte_table.range(),
+            TableRange::up_to(256),
+            "bit decomposition needs a 0..256 byte table"
+        );
         let s_decomp = meta.selector();
         let s_lookup = meta.complex_selector();
 
@@ -70,7 +86,7 @@ impl<F: PrimeField> BitDecompositionConfig<F> {
             meta.lookup(|meta| {
                 let s_lookup = meta.query_selector(s_lookup);
                 let byte_val = meta.query_advice(col, Rotation::cur());
-                vec![(s_lookup * byte_val, byte_table)]
+                vec![(byte_table.input(s_lookup, byte_val), byte_table.table)]
             });
         }
 
@@ -80,108 +96,117 @@ impl<F: PrimeField> BitDecompositionConfig<F> {
             byte_table,
             s_decomp,
             s_lookup,
-            _marker: PhantomData,
         }
     }
 
-    /// 加载256个值的小lookup table
-    fn load_byte_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        layouter.assign_table(
-            || "load 8-bit lookup table",
-            |mut table| {
-                for value in 0..256u64 {
-                    table.assign_cell(
-                        || "byte table cell",
-                        self.byte_table,
-                        value as usize,
-                        || Value::known(F::from(value)),
-                    )?;
-                }
-                Ok(())
-            },
-        )
+    /// 加载256个值的小lookup table（共享的table由 `TableRegistry` 加载）
+    pub fn load_byte_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.byte_table.load(layouter)
     }
 
     /// 分配值并进行位分解验证
-    fn assign_and_decompose(
+    ///
+    /// 字节在 `Value::map` 中计算，witness未知时（keygen）只确定布局
+    pub fn assign_and_decompose(
         &self,
-        mut layouter: impl Layouter<F>,
-        value: u32,
+        layouter: impl Layouter<F>,
+        value: Value<u32>,
     ) -> Result<AssignedCell<F, F>, Error> {
-        // 分解为4个字节
-        let byte0 = (value & 0xFF) as u8;
-        let byte1 = ((value >> 8) & 0xFF) as u8;
-        let byte2 = ((value >> 16) & 0xFF) as u8;
-        let byte3 = ((value >> 24) & 0xFF) as u8;
+        self.decompose(layouter, value)
+            .map(|(value_cell, _)| value_cell)
+    }
 
+    /// 与 `assign_and_decompose` 相同，同时返回4个已范围检查的字节（小端）
+    pub fn decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u32>,
+    ) -> Result<(AssignedCell<F, F>, [AssignedCell<F, F>; 4]), Error> {
         layouter.assign_region(
             || "bit decomposition",
             |mut region| {
-                // 启用选择器
-                self.s_decomp.enable(&mut region, 0)?;
-                self.s_lookup.enable(&mut region, 0)?;
-
                 // 分配原始值
                 let value_cell = region.assign_advice(
                     || "value",
                     self.value,
                     0,
-                    || Value::known(F::from(value as u64)),
+                    || value.map(|v| F::from(v as u64)),
                 )?;
 
-                // 分配分解后的字节
-                region.assign_advice(
-                    || "byte0",
-                    self.bytes[0],
-                    0,
-                    || Value::known(F::from(byte0 as u64)),
-                )?;
-                region.assign_advice(
-                    || "byte1",
-                    self.bytes[1],
-                    0,
-                    || Value::known(F::from(byte1 as u64)),
-                )?;
-                region.assign_advice(
-                    || "byte2",
-                    self.bytes[2],
-                    0,
-                    || Value::known(F::from(byte2 as u64)),
-                )?;
-                region.assign_advice(
-                    || "byte3",
-                    self.bytes[3],
-                    0,
-                    || Value::known(F::from(byte3 as u64)),
-                )?;
+                let bytes = self.assign_bytes(&mut region, value_cell.value().copied())?;
+                Ok((value_cell, bytes))
+            },
+        )
+    }
+
+    /// 对已分配的cell做范围检查（例如其他芯片的输出）
+    ///
+    /// cell通过copy约束复制到分解region，超出32位的值无法通过位分解约束
+    pub fn range_check_cell(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "bit decomposition of assigned cell",
+            |mut region| {
+                let value_cell = cell.copy_advice(|| "value", &mut region, self.value, 0)?;
 
+                self.assign_bytes(&mut region, cell.value().copied())?;
                 Ok(value_cell)
             },
         )
     }
+
+    /// 启用选择器并在第0行分配value的低4个字节
+    fn assign_bytes(
+        &self,
+        region: &mut Region<'_, F>,
+        value: Value<F>,
+    ) -> Result<[AssignedCell<F, F>; 4], Error> {
+        self.s_decomp.enable(region, 0)?;
+        self.s_lookup.enable(region, 0)?;
+
+        let windows = value.map(|v| decompose_windows(&v, 8, 4));
+        let mut bytes = Vec::with_capacity(4);
+        for (i, &column) in self.bytes.iter().enumerate() {
+            let byte = windows.as_ref().map(|w| F::from(w[i]));
+            bytes.push(region.assign_advice(|| format!("byte{i}"), column, 0, || byte)?);
+        }
+        Ok(bytes.try_into().unwrap())
+    }
 }
 
 /// ==============================================
 /// 方案2：二进制约束
 /// 使用二进制位分解进行大范围检查，不需要lookup table
+/// 位宽 `BITS` 默认32，必须小于域的位数（Pallas/Vesta最多252位）
 /// ==============================================
 
 #[derive(Debug, Clone)]
-struct BinaryRangeConfig<F: PrimeField> {
+pub struct BinaryRangeConfig<F: PrimeField, const BITS: usize = 32> {
     value: Column<Advice>,
     // 二进制位表示
-    bits: [Column<Advice>; 32], // 32位二进制
+    bits: [Column<Advice>; BITS], // BITS位二进制
     s_binary: Selector,
     s_composition: Selector,
     _marker: PhantomData<F>,
 }
 
-impl<F: PrimeField> BinaryRangeConfig<F> {
-    fn configure(
+impl<F: PrimeField, const BITS: usize> BinaryRangeConfig<F, BITS> {
+    /// 配置BITS个布尔约束和组合约束
+    pub fn configure(
         meta: &mut ConstraintSystem<F>,
         value: Column<Advice>,
-        bits: [Column<Advice>; 32],
+        bits: [Column<Advice>; BITS],
     ) -> Self {
+        // Σ bit_i · 2^i 必须小于模数，否则超出范围的值可以回绕
+        assert!(
+            BITS > 0 && BITS < F::NUM_BITS as usize,
+            "binary range check needs 0 < BITS < {}",
+            F::NUM_BITS
+        );
+
         let s_binary = meta.selector();
         let s_composition = meta.selector();
 
@@ -192,7 +217,7 @@ impl<F: PrimeField> BinaryRangeConfig<F> {
         }
 
         // 二进制约束：确保每一位都是0或1
-        for (i, &bit_col) in bits.iter().enumerate() {
+        for &bit_col in bits.iter() {
             meta.create_gate("binary_constraint", |meta| {
                 let s_binary = meta.query_selector(s_binary);
                 let bit = meta.query_advice(bit_col, Rotation::cur());
@@ -207,11 +232,13 @@ impl<F: PrimeField> BinaryRangeConfig<F> {
             let s_composition = meta.query_selector(s_composition);
             let value = meta.query_advice(value, Rotation::cur());
 
+            // 2^i 在域中逐次倍增计算，位宽超过64位也不会溢出
             let mut composition = Expression::Constant(F::ZERO);
-            for (i, &bit_col) in bits.iter().enumerate() {
+            let mut power_of_two = F::ONE;
+            for &bit_col in bits.iter() {
                 let bit = meta.query_advice(bit_col, Rotation::cur());
-                let power_of_two = F::from(1u64 << i);
                 composition = composition + bit * Expression::Constant(power_of_two);
+                power_of_two = power_of_two.double();
             }
 
             vec![s_composition * (value - composition)]
@@ -227,41 +254,56 @@ impl<F: PrimeField> BinaryRangeConfig<F> {
     }
 
     /// 分配值并进行二进制分解验证
-    fn assign_and_decompose(
+    ///
+    /// 二进制位在 `Value::map` 中计算，witness未知时（keygen）只确定布局
+    pub fn assign_and_decompose(
         &self,
         mut layouter: impl Layouter<F>,
-        value: u32,
+        value: Value<F>,
     ) -> Result<AssignedCell<F, F>, Error> {
         layouter.assign_region(
             || "binary decomposition",
             |mut region| {
-                // 启用选择器
-                self.s_binary.enable(&mut region, 0)?;
-                self.s_composition.enable(&mut region, 0)?;
-
                 // 分配原始值
-                let value_cell = region.assign_advice(
-                    || "value",
-                    self.value,
-                    0,
-                    || Value::known(F::from(value as u64)),
-                )?;
+                let value_cell = region.assign_advice(|| "value", self.value, 0, || value)?;
 
-                // 分解为32个二进制位
-                for i in 0..32 {
-                    let bit = (value >> i) & 1;
-                    region.assign_advice(
-                        || "bit",
-                        self.bits[i],
-                        0,
-                        || Value::known(F::from(bit as u64)),
-                    )?;
-                }
+                self.assign_bits(&mut region, value)?;
+                Ok(value_cell)
+            },
+        )
+    }
+
+    /// 对已分配的cell做范围检查（例如其他芯片的输出）
+    ///
+    /// cell通过copy约束复制到分解region，超出BITS位的值无法通过组合约束
+    pub fn range_check_cell(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "binary decomposition of assigned cell",
+            |mut region| {
+                let value_cell = cell.copy_advice(|| "value", &mut region, self.value, 0)?;
 
+                self.assign_bits(&mut region, cell.value().copied())?;
                 Ok(value_cell)
             },
         )
     }
+
+    /// 启用选择器并在第0行分配BITS个二进制位
+    fn assign_bits(&self, region: &mut Region<'_, F>, value: Value<F>) -> Result<(), Error> {
+        self.s_binary.enable(region, 0)?;
+        self.s_composition.enable(region, 0)?;
+
+        let bits = value.map(|v| decompose_windows(&v, 1, BITS));
+        for (i, &column) in self.bits.iter().enumerate() {
+            let bit = bits.as_ref().map(|b| F::from(b[i]));
+            region.assign_advice(|| "bit", column, 0, || bit)?;
+        }
+        Ok(())
+    }
 }
 
 /// ==============================================
@@ -269,15 +311,25 @@ impl<F: PrimeField> BinaryRangeConfig<F> {
 /// ==============================================
 
 #[derive(Default)]
-struct BitDecompositionCircuit<F: PrimeField> {
-    value: u32,
+pub struct BitDecompositionCircuit<F: PrimeField> {
+    pub value: Value<u32>,
     _marker: PhantomData<F>,
 }
 
+impl<F: PrimeField> BitDecompositionCircuit<F> {
+    pub fn new(value: u32) -> Self {
+        Self {
+            value: Value::known(value),
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<F: PrimeField> Circuit<F> for BitDecompositionCircuit<F> {
     type Config = BitDecompositionConfig<F>;
     type FloorPlanner = SimpleFloorPlanner;
 
+    /// 值未知，keygen不依赖任何witness
     fn without_witnesses(&self) -> Self {
         Self::default()
     }
@@ -290,7 +342,7 @@ impl<F: PrimeField> Circuit<F> for BitDecompositionCircuit<F> {
             meta.advice_column(),
             meta.advice_column(),
         ];
-        let byte_table = meta.lookup_table_column();
+        let byte_table = LookUpTable::configure(meta, TableRange::up_to(256));
 
         BitDecompositionConfig::configure(meta, value, bytes, byte_table)
     }
@@ -315,26 +367,35 @@ impl<F: PrimeField> Circuit<F> for BitDecompositionCircuit<F> {
 /// ==============================================
 
 #[derive(Default)]
-struct BinaryRangeCircuit<F: PrimeField> {
-    value: u32,
-    _marker: PhantomData<F>,
+pub struct BinaryRangeCircuit<F: PrimeField, const BITS: usize = 32> {
+    pub value: Value<F>,
 }
 
-impl<F: PrimeField> Circuit<F> for BinaryRangeCircuit<F> {
-    type Config = BinaryRangeConfig<F>;
+impl<F: PrimeField, const BITS: usize> BinaryRangeCircuit<F, BITS> {
+    pub fn new(value: u32) -> Self {
+        Self::from_field(F::from(value as u64))
+    }
+
+    /// 任意域元素（可以超过64位）
+    pub fn from_field(value: F) -> Self {
+        Self {
+            value: Value::known(value),
+        }
+    }
+}
+
+impl<F: PrimeField, const BITS: usize> Circuit<F> for BinaryRangeCircuit<F, BITS> {
+    type Config = BinaryRangeConfig<F, BITS>;
     type FloorPlanner = SimpleFloorPlanner;
 
+    /// 值未知，keygen不依赖任何witness
     fn without_witnesses(&self) -> Self {
         Self::default()
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let value = meta.advice_column();
-        let mut bits = Vec::new();
-        for _ in 0..32 {
-            bits.push(meta.advice_column());
-        }
-        let bits: [Column<Advice>; 32] = bits.try_into().unwrap();
+        let bits = std::array::from_fn(|_| meta.advice_column());
 
         BinaryRangeConfig::configure(meta, value, bits)
     }
@@ -351,22 +412,265 @@ impl<F: PrimeField> Circuit<F> for BinaryRangeCircuit<F> {
     }
 }
 
+/// ==============================================
+/// 批量电路：一次检查多个值
+/// 用于对比两种方案在值的个数增长时的开销
+/// ==============================================
+
+#[derive(Default)]
+pub struct BitDecompositionBatchCircuit<F: PrimeField> {
+    pub values: Vec<Value<u32>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BitDecompositionBatchCircuit<F> {
+    pub fn new(values: Vec<u32>) -> Self {
+        Self {
+            values: values.into_iter().map(Value::known).collect(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for BitDecompositionBatchCircuit<F> {
+    type Config = BitDecompositionConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    /// 布局取决于值的个数，因此保留长度，值全部未知
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitDecompositionCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        // lookup table只加载一次，所有值共享
+        config.load_byte_table(&mut layouter)?;
+
+        for (i, &value) in self.values.iter().enumerate() {
+            config.assign_and_decompose(layouter.namespace(|| format!("decompose {i}")), value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct BinaryRangeBatchCircuit<F: PrimeField> {
+    pub values: Vec<Value<u32>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BinaryRangeBatchCircuit<F> {
+    pub fn new(values: Vec<u32>) -> Self {
+        Self {
+            values: values.into_iter().map(Value::known).collect(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for BinaryRangeBatchCircuit<F> {
+    type Config = BinaryRangeConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    /// 布局取决于值的个数，因此保留长度，值全部未知
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BinaryRangeCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        for (i, &value) in self.values.iter().enumerate() {
+            config.assign_and_decompose(
+                layouter.namespace(|| format!("binary_decompose {i}")),
+                value.map(|v| F::from(v as u64)),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// ==============================================
+/// 方案对比：同一批值分别用两种方案证明
+/// 输出列数、行数、lookup数、门次数、证明/验证耗时和证明大小
+/// ==============================================
+
+/// 单个方案的对比结果
+#[derive(Debug, Clone)]
+pub struct SchemeReport {
+    pub scheme: &'static str,
+    pub num_values: usize,
+    pub stats: CircuitStats,
+    pub keygen_time: Duration,
+    pub proving_time: Duration,
+    pub verification_time: Duration,
+    pub proof_size: usize,
+}
+
+/// 生成确定性的测试值，便于不同时间的报告相互比较
+///
+/// 样本按u32下标生成，`count` 超过 `u32::MAX` 时返回错误而不是截断
+pub fn sample_values(count: usize) -> Result<Vec<u32>, TryFromIntError> {
+    let count = u32::try_from(count)?;
+    Ok((0..count)
+        .map(|i| i.wrapping_mul(0x9E37_79B9) ^ 0xA5A5_A5A5)
+        .collect())
+}
+
+/// 以最小k完成keygen、证明和验证，记录每一步的开销
+fn measure_scheme<C: Circuit<Fp>>(
+    scheme: &'static str,
+    num_values: usize,
+    circuit: C,
+) -> Result<SchemeReport, Error> {
+    let stats = CircuitStats::measure(&circuit)?;
+
+    let start = Instant::now();
+    let (params, pk) = pipeline::setup(stats.minimal_k, &circuit)?;
+    let keygen_time = start.elapsed();
+
+    let start = Instant::now();
+    let proof = pipeline::prove(&params, &pk, circuit, &[])?;
+    let proving_time = start.elapsed();
+
+    let start = Instant::now();
+    pipeline::verify(&params, pk.get_vk(), &[], &proof)?;
+    let verification_time = start.elapsed();
+
+    Ok(SchemeReport {
+        scheme,
+        num_values,
+        stats,
+        keygen_time,
+        proving_time,
+        verification_time,
+        proof_size: proof.len(),
+    })
+}
+
+/// 对同一批值运行两种方案
+pub fn compare_schemes(values: &[u32]) -> Result<Vec<SchemeReport>, Error> {
+    Ok(vec![
+        measure_scheme(
+            "bit-decomposition",
+            values.len(),
+            BitDecompositionBatchCircuit::<Fp>::new(values.to_vec()),
+        )?,
+        measure_scheme(
+            "binary-range",
+            values.len(),
+            BinaryRangeBatchCircuit::<Fp>::new(values.to_vec()),
+        )?,
+    ])
+}
+
+const REPORT_FIELDS: [&str; 14] = [
+    "scheme",
+    "num_values",
+    "k",
+    "advice_columns",
+    "fixed_columns",
+    "selectors",
+    "lookups",
+    "max_degree",
+    "rows",
+    "table_rows",
+    "keygen_ms",
+    "proving_ms",
+    "verification_ms",
+    "proof_size",
+];
+
+impl SchemeReport {
+    /// 与 `REPORT_FIELDS` 一一对应的字段值，字符串之外都是数字
+    fn values(&self) -> [String; 14] {
+        let ms = |d: Duration| format!("{:.3}", d.as_secs_f64() * 1000.0);
+        [
+            self.scheme.to_string(),
+            self.num_values.to_string(),
+            self.stats.minimal_k.to_string(),
+            self.stats.advice_columns.to_string(),
+            self.stats.fixed_columns.to_string(),
+            self.stats.selectors.to_string(),
+            self.stats.lookups.to_string(),
+            self.stats.max_degree.to_string(),
+            self.stats.rows_used().to_string(),
+            self.stats.table_rows.to_string(),
+            ms(self.keygen_time),
+            ms(self.proving_time),
+            ms(self.verification_time),
+            self.proof_size.to_string(),
+        ]
+    }
+}
+
+/// CSV报告：表头 + 每个方案一行
+pub fn reports_to_csv(reports: &[SchemeReport]) -> String {
+    let mut out = REPORT_FIELDS.join(",");
+    out.push('\n');
+    for report in reports {
+        out.push_str(&report.values().join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// JSON报告：对象数组，scheme为字符串，其余字段为数字
+pub fn reports_to_json(reports: &[SchemeReport]) -> String {
+    let objects: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let fields: Vec<String> = REPORT_FIELDS
+                .iter()
+                .zip(report.values())
+                .map(|(name, value)| match *name {
+                    "scheme" => format!("\"{name}\": \"{value}\""),
+                    _ => format!("\"{name}\": {value}"),
+                })
+                .collect();
+            format!("  {{{}}}", fields.join(", "))
+        })
+        .collect();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use crate::{
+        basic::multi_chip_design::{MulChip, MulConfig},
+        stats::CircuitStats,
+    };
+    use halo2_proofs::dev::MockProver;
 
     #[test]
     fn test_bit_decomposition_range_check() {
-        let k = 10;
-
         // 测试一个在2^32范围内的值
         let test_value = 0x12345678u32; // 305419896
 
-        let circuit = BitDecompositionCircuit::<Fp> {
-            value: test_value,
-            _marker: PhantomData,
-        };
+        let circuit = BitDecompositionCircuit::<Fp>::new(test_value);
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
 
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()));
@@ -379,15 +683,11 @@ mod tests {
 
     #[test]
     fn test_binary_range_check() {
-        let k = 10;
-
         // 测试一个2^32范围内的值
         let test_value = 0xFFFFFFFFu32; // 最大32位值
 
-        let circuit = BinaryRangeCircuit::<Fp> {
-            value: test_value,
-            _marker: PhantomData,
-        };
+        let circuit = BinaryRangeCircuit::<Fp>::new(test_value);
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
 
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()));
@@ -397,4 +697,196 @@ mod tests {
             test_value, test_value
         );
     }
+
+    #[test]
+    fn test_witness_free_keygen() {
+        // keygen只看到未知值，同一组密钥可以为任意值生成证明
+        let empty = BitDecompositionCircuit::<Fp>::default();
+        let k = CircuitStats::measure(&empty).unwrap().minimal_k;
+        let (params, pk) = pipeline::setup(k, &empty).unwrap();
+        for value in [0, 0x12345678, u32::MAX] {
+            let proof =
+                pipeline::prove(&params, &pk, BitDecompositionCircuit::new(value), &[]).unwrap();
+            assert!(pipeline::verify(&params, pk.get_vk(), &[], &proof).is_ok());
+        }
+
+        let empty = BinaryRangeBatchCircuit::<Fp>::new(vec![0; 2]).without_witnesses();
+        let k = CircuitStats::measure(&empty).unwrap().minimal_k;
+        let (params, pk) = pipeline::setup(k, &empty).unwrap();
+        let circuit = BinaryRangeBatchCircuit::new(vec![1, u32::MAX]);
+        let proof = pipeline::prove(&params, &pk, circuit, &[]).unwrap();
+        assert!(pipeline::verify(&params, pk.get_vk(), &[], &proof).is_ok());
+
+        println!("无witness的keygen测试通过！");
+    }
+
+    /// 两种方案都检查 `MulChip` 的乘积 a × b
+    #[derive(Default)]
+    struct ProductRangeCircuit {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for ProductRangeCircuit {
+        type Config = (
+            Column<Advice>,
+            MulConfig,
+            BitDecompositionConfig<Fp>,
+            BinaryRangeConfig<Fp>,
+        );
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let input = meta.advice_column();
+            meta.enable_equality(input);
+
+            let mul_advice = [
+                meta.advice_column(),
+                meta.advice_column(),
+                meta.advice_column(),
+            ];
+            let constant = meta.fixed_column();
+            let mul = MulChip::configure(meta, mul_advice, constant);
+
+            (
+                input,
+                mul,
+                BitDecompositionCircuit::<Fp>::configure(meta),
+                BinaryRangeCircuit::<Fp>::configure(meta),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (input, mul, bit, binary): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            bit.load_byte_table(&mut layouter)?;
+
+            let (a, b) = layouter.assign_region(
+                || "load inputs",
+                |mut region| {
+                    let a = region.assign_advice(|| "a", input, 0, || self.a)?;
+                    let b = region.assign_advice(|| "b", input, 1, || self.b)?;
+                    Ok((a, b))
+                },
+            )?;
+
+            let product = MulChip::construct(mul).mul_with_constant(
+                layouter.namespace(|| "a × b"),
+                a,
+                b,
+                Fp::one(),
+            )?;
+
+            bit.range_check_cell(layouter.namespace(|| "bit range check"), &product)?;
+            binary.range_check_cell(layouter.namespace(|| "binary range check"), &product)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_range_check_assigned_cell() {
+        let run = |a: u64, b: u64| {
+            let circuit = ProductRangeCircuit {
+                a: Value::known(Fp::from(a)),
+                b: Value::known(Fp::from(b)),
+            };
+            let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+            MockProver::run(k, &circuit, vec![]).unwrap().verify()
+        };
+
+        // 乘积 0xFFFE0001 < 2^32
+        assert_eq!(run(0xFFFF, 0xFFFF), Ok(()));
+        // 乘积 2^32 超出范围，两种方案都应失败
+        let failures = run(0x10000, 0x10000).unwrap_err();
+        let failed_gates: Vec<String> = failures.iter().map(|f| format!("{f}")).collect();
+        assert!(failed_gates.iter().any(|f| f.contains("bit_decomposition")));
+        assert!(
+            failed_gates
+                .iter()
+                .any(|f| f.contains("composition_constraint"))
+        );
+
+        println!("已分配cell的范围检查测试通过！");
+    }
+
+    fn check_binary_width<const BITS: usize>() {
+        let run = |value: Fp| {
+            let circuit = BinaryRangeCircuit::<Fp, BITS>::from_field(value);
+            let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+            MockProver::run(k, &circuit, vec![]).unwrap().verify()
+        };
+
+        let bound = Fp::from(2).pow_vartime([BITS as u64]);
+        assert_eq!(run(Fp::zero()), Ok(()));
+        assert_eq!(run(Fp::one()), Ok(()));
+        assert_eq!(run(bound - Fp::one()), Ok(()));
+
+        // 2^BITS、2^BITS + 1 和 p - 1 都超出范围
+        assert!(run(bound).is_err());
+        assert!(run(bound + Fp::one()).is_err());
+        assert!(run(-Fp::one()).is_err());
+
+        println!("{BITS}位二进制范围检查通过");
+    }
+
+    #[test]
+    fn test_binary_range_widths() {
+        check_binary_width::<8>();
+        check_binary_width::<16>();
+        check_binary_width::<64>();
+        check_binary_width::<128>();
+        check_binary_width::<252>();
+    }
+
+    #[test]
+    fn test_scheme_comparison_report() {
+        let values = sample_values(3).unwrap();
+        // 超出u32的个数返回错误而不是截断
+        if let Ok(count) = usize::try_from(u64::from(u32::MAX) + 1) {
+            assert!(sample_values(count).is_err());
+        }
+        let reports = compare_schemes(&values).unwrap();
+        assert_eq!(reports.len(), 2);
+
+        let (bit, binary) = (&reports[0], &reports[1]);
+        assert_eq!(bit.scheme, "bit-decomposition");
+        assert_eq!(binary.scheme, "binary-range");
+
+        // 位分解：5个advice列 + 4个lookup，行数由256行的字节表决定
+        assert_eq!(bit.stats.advice_columns, 5);
+        assert_eq!(bit.stats.lookups, 4);
+        assert_eq!(bit.stats.table_rows, 256);
+        assert_eq!(bit.stats.region_rows, values.len());
+
+        // 二进制约束：33个advice列，没有lookup，每个值占一行
+        assert_eq!(binary.stats.advice_columns, 33);
+        assert_eq!(binary.stats.lookups, 0);
+        assert_eq!(binary.stats.region_rows, values.len());
+        assert!(binary.stats.minimal_k < bit.stats.minimal_k);
+
+        for report in &reports {
+            assert_eq!(report.num_values, values.len());
+            assert!(report.proof_size > 0);
+        }
+
+        let csv = reports_to_csv(&reports);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("scheme,num_values,k,"));
+        assert!(lines[1].starts_with("bit-decomposition,3,"));
+        assert_eq!(lines[2].split(',').count(), REPORT_FIELDS.len());
+
+        let json = reports_to_json(&reports);
+        assert!(json.starts_with('['));
+        assert!(json.contains("\"scheme\": \"binary-range\""));
+        assert!(json.contains(&format!("\"proof_size\": {}", bit.proof_size)));
+
+        print!("{csv}");
+    }
 }
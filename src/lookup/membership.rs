@@ -0,0 +1,290 @@
+/// ==============================================
+/// 集合成员检查
+/// ==============================================
+///
+/// 证明私有值属于任意给定的集合（国家代码、资产ID等），而不是连续区间。
+///
+/// 单列table无法排除0：selector关闭的行输入为0，table必须包含0，
+/// 于是0总能通过检查。这里用两列 `(tag, value)`：集合元素存为 `(1, v)`，
+/// 另加一行 `(0, 0)` 供关闭的行使用，查表输入为 `(q, q · value)`。
+///
+/// | value | q |     | tag | set  |
+/// |-------|---|     |-----|------|
+/// |   v   | 1 |     |  0  |  0   |
+/// |       | 0 |     |  1  | v_0  |
+/// |       | 0 |     |  1  | ...  |
+use std::{collections::HashSet, fs, io, path::Path};
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::{bitwise::TupleTable, table::parse_field};
+
+/// 允许的值的集合（去重，保持首次出现的顺序）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueSet<F: PrimeField> {
+    values: Vec<F>,
+    /// 每个值的 `to_repr()` 字节，用于去重和成员判断
+    index: HashSet<Vec<u8>>,
+}
+
+impl<F: PrimeField> ValueSet<F> {
+    pub fn new(values: impl IntoIterator<Item = F>) -> Self {
+        let mut set = Vec::new();
+        let mut index = HashSet::new();
+        for value in values {
+            if index.insert(repr_bytes(&value)) {
+                set.push(value);
+            }
+        }
+        assert!(!set.is_empty(), "value set must not be empty");
+        Self { values: set, index }
+    }
+
+    pub fn from_u64s(values: &[u64]) -> Self {
+        Self::new(values.iter().map(|&v| F::from(v)))
+    }
+
+    /// 从文本文件读取，每行一个十进制或 `0x` 开头的十六进制数（与命令行的格式相同），
+    /// `#` 之后为注释
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut values = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let value = parse_field(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: invalid value {line:?}", line_no + 1),
+                )
+            })?;
+            values.push(value);
+        }
+        if values.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "value set is empty",
+            ));
+        }
+        Ok(Self::new(values))
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    pub fn contains(&self, value: &F) -> bool {
+        self.index.contains(&repr_bytes(value))
+    }
+}
+
+fn repr_bytes<F: PrimeField>(value: &F) -> Vec<u8> {
+    value.to_repr().as_ref().to_vec()
+}
+
+/// `(tag, value)` 两列的成员table
+#[derive(Debug, Clone)]
+pub struct MembershipTable<F: PrimeField> {
+    pub table: TupleTable<F, 2>,
+}
+
+impl<F: PrimeField> MembershipTable<F> {
+    /// 分配table列，集合内容在加载时才确定
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            table: TupleTable::configure(meta),
+        }
+    }
+
+    /// 加载 `(0, 0)` 和集合中的每个 `(1, v)`
+    pub fn load(&self, layouter: &mut impl Layouter<F>, set: &ValueSet<F>) -> Result<(), Error> {
+        let rows: Vec<[F; 2]> = std::iter::once([F::ZERO, F::ZERO])
+            .chain(set.values().iter().map(|&v| [F::ONE, v]))
+            .collect();
+        self.table.load(layouter, &rows)
+    }
+}
+
+/// 约束已分配的cell是集合成员
+#[derive(Debug, Clone)]
+pub struct MembershipConfig<F: PrimeField> {
+    value: Column<Advice>,
+    q_member: Selector,
+    table: MembershipTable<F>,
+}
+
+impl<F: PrimeField> MembershipConfig<F> {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        table: MembershipTable<F>,
+    ) -> Self {
+        let q_member = meta.complex_selector();
+        meta.enable_equality(value);
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_member);
+            let v = meta.query_advice(value, Rotation::cur());
+            let [tag, set] = table.table.columns;
+            vec![(q.clone(), tag), (q * v, set)]
+        });
+
+        MembershipConfig {
+            value,
+            q_member,
+            table,
+        }
+    }
+
+    pub fn load_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        set: &ValueSet<F>,
+    ) -> Result<(), Error> {
+        self.table.load(layouter, set)
+    }
+
+    /// 分配新值并检查它属于集合
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "set membership",
+            |mut region| {
+                self.q_member.enable(&mut region, 0)?;
+                region.assign_advice(|| "member", self.value, 0, || value)
+            },
+        )
+    }
+
+    /// 检查已分配的cell属于集合，cell通过copy约束复制进来
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "set membership of assigned cell",
+            |mut region| {
+                self.q_member.enable(&mut region, 0)?;
+                cell.copy_advice(|| "member", &mut region, self.value, 0)
+            },
+        )
+    }
+}
+
+/// ==============================================
+/// 测试电路：检查若干私有值都属于允许列表
+/// ==============================================
+
+pub struct MembershipCircuit<F: PrimeField> {
+    /// 允许列表决定table内容，不是witness
+    pub set: ValueSet<F>,
+    pub values: Vec<Value<F>>,
+}
+
+impl<F: PrimeField> MembershipCircuit<F> {
+    pub fn new(set: ValueSet<F>, values: Vec<F>) -> Self {
+        Self {
+            set,
+            values: values.into_iter().map(Value::known).collect(),
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for MembershipCircuit<F> {
+    type Config = MembershipConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    /// 保留允许列表和值的个数
+    fn without_witnesses(&self) -> Self {
+        Self {
+            set: self.set.clone(),
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let value = meta.advice_column();
+        let table = MembershipTable::configure(meta);
+        MembershipConfig::configure(meta, value, table)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_table(&mut layouter, &self.set)?;
+        for (i, &value) in self.values.iter().enumerate() {
+            config.witness_check(layouter.namespace(|| format!("value {i}")), value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    fn run(set: &ValueSet<Fp>, values: Vec<Fp>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = MembershipCircuit::new(set.clone(), values);
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        MockProver::run(k, &circuit, vec![]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_set_membership() {
+        // ISO 3166 数字国家代码：中国、德国、美国
+        let set = ValueSet::<Fp>::from_u64s(&[156, 276, 840]);
+
+        assert_eq!(run(&set, vec![Fp::from(840), Fp::from(156)]), Ok(()));
+        // 非成员
+        assert!(run(&set, vec![Fp::from(840), Fp::from(250)]).is_err());
+        // 0不在集合中，也不能通过
+        assert!(run(&set, vec![Fp::zero()]).is_err());
+        // 成员的相反数 p - 156 是另一个域元素，同样不是成员
+        assert!(run(&set, vec![-Fp::from(156)]).is_err());
+
+        // 0显式加入集合后可以通过
+        let with_zero = ValueSet::<Fp>::from_u64s(&[0, 7]);
+        assert_eq!(run(&with_zero, vec![Fp::zero(), Fp::from(7)]), Ok(()));
+
+        println!("集合成员检查测试通过！");
+    }
+
+    #[test]
+    fn test_value_set_from_file() {
+        let path = std::env::temp_dir().join(format!(
+            "halo2-demo-value-set-from-file-{}.txt",
+            std::process::id()
+        ));
+        fs::write(&path, "# asset ids\n1001\n0x3ea  # 1002\n\n1001\n").unwrap();
+
+        let set = ValueSet::<Fp>::from_file(&path).unwrap();
+        assert_eq!(set.values(), &[Fp::from(1001), Fp::from(1002)]);
+        assert_eq!(run(&set, vec![Fp::from(1002)]), Ok(()));
+        assert!(run(&set, vec![Fp::from(1003)]).is_err());
+
+        fs::write(&path, "1001\nnot-a-number\n").unwrap();
+        let err = ValueSet::<Fp>::from_file(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_file(&path).unwrap();
+        println!("从文件加载集合测试通过！");
+    }
+}
@@ -1,3 +1,11 @@
+pub mod binary_rows;
+pub mod bitwise;
+pub mod canonical;
+pub mod comparison;
 pub mod large_range_analysis;
+pub mod membership;
 pub mod rangecheck_lookup;
+pub mod running_sum;
 pub mod table;
+pub mod table_registry;
+pub mod tagged;
@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-// Problem to prove:  a in [0, RANGE]
+// Problem to prove:  a in [lo, hi]（MyCircuit 中为 [0, RANGE)）
 use halo2_proofs::{
     arithmetic::Field,
     circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
@@ -17,24 +17,43 @@ use super::table::*;
 /// |   a   |    1    |    0    |
 /// |       |    0    |    1    |
 /// |       |    0    |  ...    |
-/// |       |    0    |  RANGE  |
+/// |       |    0    | hi - lo |
+///
+/// lookup的输入为 `q_lookup · (a - lo)`
 
-struct ACell<F: PrimeField>(AssignedCell<Assigned<F>, F>);
+/// `RangeConfig::assign` 返回的已分配cell
 #[derive(Debug, Clone)]
-struct RangeConfig<F: PrimeField, const RANGE: usize, const NUM: usize> {
+pub struct ACell<F: PrimeField>(pub AssignedCell<Assigned<F>, F>);
+/// 检查 `NUM` 个值都落在 `range` 内
+#[derive(Debug, Clone)]
+pub struct RangeConfig<F: PrimeField, const NUM: usize> {
     value: Column<Advice>,
-    table: LookUpTable<F, RANGE>,
+    table: LookUpTable<F>,
     q_lookup: Selector,
 }
 
-impl<F: PrimeField, const RANGE: usize, const NUM: usize> RangeConfig<F, RANGE, NUM> {
-    fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+impl<F: PrimeField, const NUM: usize> RangeConfig<F, NUM> {
+    /// 在 `value` 列上配置lookup，并创建存放 `range` 的table列
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        range: TableRange,
+    ) -> Self {
+        let table = LookUpTable::configure(meta, range);
+        Self::configure_with_table(meta, value, table)
+    }
+
+    /// 在 `value` 列上配置lookup，table由调用方提供（例如来自 `TableRegistry`）
+    pub fn configure_with_table(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        table: LookUpTable<F>,
+    ) -> Self {
         let q_lookup = meta.complex_selector();
-        let table = LookUpTable::<F, RANGE>::configure(meta);
         meta.lookup(|meta| {
             let q_lookup = meta.query_selector(q_lookup);
             let v = meta.query_advice(value, Rotation::cur());
-            vec![(q_lookup * v, table.table)]
+            vec![(table.input(q_lookup, v), table.table)]
         });
 
         RangeConfig {
@@ -44,7 +63,13 @@ impl<F: PrimeField, const RANGE: usize, const NUM: usize> RangeConfig<F, RANGE,
         }
     }
 
-    fn assign(
+    /// 加载table
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.table.load(layouter)
+    }
+
+    /// 在一个region中连续分配所有值，返回最后一个cell
+    pub fn assign(
         &self,
         mut layouter: impl Layouter<F>,
         value: [Value<Assigned<F>>; NUM],
@@ -69,12 +94,13 @@ impl<F: PrimeField, const RANGE: usize, const NUM: usize> RangeConfig<F, RANGE,
     }
 }
 
+/// 检查 `NUM` 个值都落在 `[0, RANGE)` 内
 #[derive(Debug)]
-struct MyCircuit<F: PrimeField, const RANGE: usize, const NUM: usize> {
-    value: [Value<Assigned<F>>; NUM],
+pub struct MyCircuit<F: PrimeField, const RANGE: usize, const NUM: usize> {
+    pub value: [Value<Assigned<F>>; NUM],
 }
 
-impl<F: PrimeField, const RANGE: usize, const NUM: usize> MyCircuit<F, RANGE, NUM> {
+impl<F: PrimeField, const RANGE: usize, const NUM: usize> Default for MyCircuit<F, RANGE, NUM> {
     fn default() -> Self {
         let mut values = vec![];
         for i in 0..NUM {
@@ -87,7 +113,7 @@ impl<F: PrimeField, const RANGE: usize, const NUM: usize> MyCircuit<F, RANGE, NU
 }
 
 impl<F: PrimeField, const RANGE: usize, const NUM: usize> Circuit<F> for MyCircuit<F, RANGE, NUM> {
-    type Config = RangeConfig<F, RANGE, NUM>;
+    type Config = RangeConfig<F, NUM>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -96,7 +122,7 @@ impl<F: PrimeField, const RANGE: usize, const NUM: usize> Circuit<F> for MyCircu
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let advice = meta.advice_column();
-        RangeConfig::configure(meta, advice)
+        RangeConfig::configure(meta, advice, TableRange::up_to(RANGE))
     }
 
     fn synthesize(
@@ -104,9 +130,7 @@ impl<F: PrimeField, const RANGE: usize, const NUM: usize> Circuit<F> for MyCircu
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        config
-            .table
-            .load(&mut layouter.namespace(|| "lookup col"))?;
+        config.load_table(&mut layouter.namespace(|| "lookup col"))?;
         config.assign(layouter.namespace(|| "range check"), self.value)?;
         Ok(())
     }
@@ -117,6 +141,7 @@ mod tests {
     use halo2_proofs::{dev::MockProver, pasta::Fp};
 
     use super::*;
+    use crate::stats::CircuitStats;
 
     #[test]
     fn test_rangecheck_lookup() {
@@ -129,7 +154,7 @@ mod tests {
         let circuit = MyCircuit::<Fp, 16, NUM> {
             value: values.clone().try_into().unwrap(),
         };
-        let k = 5;
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
 
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
@@ -140,6 +165,73 @@ mod tests {
         };
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert!(prover.verify().is_err());
+
+        // [0, 16)：15 在区间内，16 不在
+        for (value, ok) in [(15, true), (16, false)] {
+            values[1] = Value::known(Assigned::from(Fp::from(value)));
+            let circuit = MyCircuit::<Fp, 16, NUM> {
+                value: values.clone().try_into().unwrap(),
+            };
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify().is_ok(), ok, "value {value}");
+        }
+    }
+
+    /// 在 `[LO, HI]`（两端都包含）上检查两个有符号值
+    struct SignedRangeCircuit<const LO: i64, const HI: i64> {
+        values: [i64; 2],
+    }
+
+    impl<const LO: i64, const HI: i64> Circuit<Fp> for SignedRangeCircuit<LO, HI> {
+        type Config = RangeConfig<Fp, 2>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { values: [LO; 2] }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = meta.advice_column();
+            RangeConfig::configure(meta, advice, TableRange::inclusive(LO, HI))
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load_table(&mut layouter.namespace(|| "lookup col"))?;
+            let values = self
+                .values
+                .map(|v| Value::known(Assigned::from(field_from_i64::<Fp>(v))));
+            config.assign(layouter.namespace(|| "range check"), values)?;
+            Ok(())
+        }
+    }
+
+    fn check_signed<const LO: i64, const HI: i64>() {
+        let run = |values| {
+            let circuit = SignedRangeCircuit::<LO, HI> { values };
+            let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+            MockProver::run(k, &circuit, vec![]).unwrap().verify()
+        };
+
+        // 两端都包含，越过任意一端都失败
+        assert_eq!(run([LO, HI]), Ok(()));
+        assert!(run([LO - 1, HI]).is_err());
+        assert!(run([LO, HI + 1]).is_err());
+    }
+
+    #[test]
+    fn test_bounded_range_lookup() {
+        // 负数下界：值平移 -LO 后落进table
+        check_signed::<{ -8 }, 7>();
+        check_signed::<{ -100 }, { -50 }>();
+        // 正数下界：table只有 HI - LO + 1 行
+        check_signed::<1000, 1255>();
+        check_signed::<0, 0>();
+
+        println!("有界lookup范围检查测试通过！");
     }
 
     #[cfg(feature = "dev-graph")]
@@ -0,0 +1,430 @@
+/// ==============================================
+/// 滑动和（running sum）范围检查
+/// ==============================================
+///
+/// 把任意位宽的值拆成K位窗口，所有窗口沿同一个advice列逐行排列：
+///   z_0 = value
+///   z_{i+1} = (z_i - a_i) / 2^K
+/// 每一行查表约束 a_i = z_i - 2^K · z_{i+1} ∈ [0, 2^K)，最后一行约束 z_W = 0，
+/// 于是 value = Σ a_i · 2^{K·i} < 2^{K·W}。
+///
+/// | z       | q_lookup | q_short | shift     | q_final | table  |
+/// |---------|----------|---------|-----------|---------|--------|
+/// | z_0     |    1     |    0    |           |    0    |   0    |
+/// | ...     |   ...    |   ...   |           |   ...   |  ...   |
+/// | z_{W-1} |    1     |    1    | 2^{K-m}   |    0    |  ...   |
+/// | z_W     |    0     |    0    |           |    1    | 2^K-1  |
+///
+/// 位宽 n 不是K的整数倍时，最后一个窗口只有 m = n - K·(W-1) 位。
+/// 短范围检查复用同一张表：a ∈ [0, 2^K) 且 a · 2^{K-m} ∈ [0, 2^K)，
+/// 两者同时成立当且仅当 a < 2^m，于是 value < 2^n 是精确的上界。
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::table::{LookUpTable, TableRange, two_pow};
+
+#[derive(Debug, Clone)]
+pub struct RunningSumConfig<F: PrimeField, const K: usize> {
+    /// 滑动和 z_i 所在的列
+    z: Column<Advice>,
+    /// 2^K 项的窗口表，可以与其他芯片共享
+    table: LookUpTable<F>,
+    /// 短范围检查的移位因子 2^{K-m}
+    shift: Column<Fixed>,
+    q_lookup: Selector,
+    q_short: Selector,
+    q_final: Selector,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField, const K: usize> RunningSumConfig<F, K> {
+    /// 编译期检查窗口位数：窗口值用u64表示，2^K 项的table区间用i64表示
+    const WINDOW_BITS: () = assert!(K > 0 && K < 63, "running sum window K must be in 1..63");
+
+    /// 在 `z` 列上配置窗口查表、短范围检查和结尾的零约束
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        z: Column<Advice>,
+        table: LookUpTable<F>,
+        shift: Column<Fixed>,
+    ) -> Self {
+        let () = Self::WINDOW_BITS;
+        assert_eq!(
+            table.range(),
+            TableRange::up_to(1 << K),
+            "running sum needs a 0..2^K window table"
+        );
+        let q_lookup = meta.complex_selector();
+        let q_short = meta.complex_selector();
+        let q_final = meta.selector();
+
+        meta.enable_equality(z);
+
+        // 每个窗口 a_i = z_i - 2^K · z_{i+1} 必须在表中
+        meta.lookup(|meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+
+            let window = z_cur - z_next * Expression::Constant(two_pow::<F>(K));
+            vec![(q_lookup * window, table.table)]
+        });
+
+        // 短范围检查：窗口左移 K-m 位后仍在表中。只在最后一个窗口启用，
+        // 此时 z_{i+1} = 0，窗口就是 z_i 本身
+        meta.lookup(|meta| {
+            let q_short = meta.query_selector(q_short);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let shift = meta.query_fixed(shift);
+
+            vec![(q_short * z_cur * shift, table.table)]
+        });
+
+        // 所有窗口减完之后剩余为0
+        meta.create_gate("running_sum_final", |meta| {
+            let q_final = meta.query_selector(q_final);
+            let z = meta.query_advice(z, Rotation::cur());
+
+            vec![q_final * z]
+        });
+
+        RunningSumConfig {
+            z,
+            table,
+            shift,
+            q_lookup,
+            q_short,
+            q_final,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 加载 0..2^K 的窗口表（共享时只需加载一次）
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.table.load(layouter)
+    }
+
+    /// 分配新值并检查它小于 2^num_bits
+    ///
+    /// 返回滑动和 z_0..z_W，z_0 即被检查的值
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+        num_bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "running sum range check",
+            |mut region| {
+                let z_0 = region.assign_advice(|| "z_0", self.z, 0, || value)?;
+                self.decompose(&mut region, z_0, num_bits)
+            },
+        )
+    }
+
+    /// 对已分配的cell做范围检查，cell通过copy约束成为 z_0
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        element: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "running sum range check of assigned cell",
+            |mut region| {
+                let z_0 = element.copy_advice(|| "z_0", &mut region, self.z, 0)?;
+                self.decompose(&mut region, z_0, num_bits)
+            },
+        )
+    }
+
+    /// 短范围检查：证明已分配的cell小于 2^num_bits（0 < num_bits < K），
+    /// 否则返回 `Error::Synthesis`
+    ///
+    /// 只占两行：窗口本身和结尾的0
+    pub fn short_range_check(
+        &self,
+        layouter: impl Layouter<F>,
+        element: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if num_bits == 0 || num_bits >= K {
+            return Err(Error::Synthesis);
+        }
+        let zs = self.copy_check(layouter, element, num_bits)?;
+        Ok(zs[0].clone())
+    }
+
+    /// 从第0行的 z_0 开始逐行分配 z_1..z_W
+    ///
+    /// `num_bits` 为0，或窗口总位数不小于域的位数（Σ a_i · 2^{K·i} 可能回绕）时
+    /// 返回 `Error::Synthesis`
+    fn decompose(
+        &self,
+        region: &mut Region<'_, F>,
+        z_0: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let num_windows = num_bits.div_ceil(K);
+        if num_bits == 0 || num_windows * K >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        let windows = z_0.value().map(|v| decompose_windows(v, K, num_windows));
+        let two_pow_k_inv = two_pow::<F>(K).invert().unwrap();
+
+        let mut z = z_0.clone();
+        let mut zs = vec![z_0];
+        for i in 0..num_windows {
+            self.q_lookup.enable(region, i)?;
+
+            let window = windows.as_ref().map(|w| F::from(w[i]));
+            let z_next = z.value().zip(window).map(|(z, a)| (*z - a) * two_pow_k_inv);
+            z = region.assign_advice(|| format!("z_{}", i + 1), self.z, i + 1, || z_next)?;
+            zs.push(z.clone());
+        }
+
+        // 最后一个窗口不满K位时加上短范围检查
+        let last_bits = num_bits - K * (num_windows - 1);
+        if last_bits < K {
+            let row = num_windows - 1;
+            self.q_short.enable(region, row)?;
+            region.assign_fixed(
+                || format!("shift 2^{}", K - last_bits),
+                self.shift,
+                row,
+                || Value::known(two_pow::<F>(K - last_bits)),
+            )?;
+        }
+
+        self.q_final.enable(region, num_windows)?;
+        Ok(zs)
+    }
+}
+
+/// 取 `value` 小端表示的低 `num_windows * window_bits` 位，每 `window_bits` 位一组
+pub fn decompose_windows<F: PrimeField>(
+    value: &F,
+    window_bits: usize,
+    num_windows: usize,
+) -> Vec<u64> {
+    let repr = value.to_repr();
+    let bytes = repr.as_ref();
+    let bit = |i: usize| bytes.get(i / 8).map_or(0, |b| ((b >> (i % 8)) & 1) as u64);
+
+    (0..num_windows)
+        .map(|w| (0..window_bits).fold(0, |acc, j| acc | (bit(w * window_bits + j) << j)))
+        .collect()
+}
+
+/// ==============================================
+/// 测试电路：检查一个 `num_bits` 位的值
+/// ==============================================
+
+pub struct RunningSumCircuit<F: PrimeField, const K: usize> {
+    pub value: Value<F>,
+    pub num_bits: usize,
+}
+
+impl<F: PrimeField, const K: usize> RunningSumCircuit<F, K> {
+    pub fn new(value: F, num_bits: usize) -> Self {
+        Self {
+            value: Value::known(value),
+            num_bits,
+        }
+    }
+}
+
+impl<F: PrimeField, const K: usize> Circuit<F> for RunningSumCircuit<F, K> {
+    type Config = RunningSumConfig<F, K>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    /// 行数取决于位宽，因此保留 `num_bits`
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            num_bits: self.num_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let z = meta.advice_column();
+        let table = LookUpTable::configure(meta, TableRange::up_to(1 << K));
+        let shift = meta.fixed_column();
+        RunningSumConfig::configure(meta, z, table, shift)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_table(&mut layouter)?;
+        config.witness_check(
+            layouter.namespace(|| "range check"),
+            self.value,
+            self.num_bits,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    const K: usize = 10;
+
+    fn run(value: Fp, num_bits: usize) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+        let circuit = RunningSumCircuit::<Fp, K>::new(value, num_bits);
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        MockProver::run(k, &circuit, vec![]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_running_sum_range_check() {
+        let two = Fp::from(2);
+        for num_bits in [10, 64, 100, 248] {
+            let num_windows = num_bits.div_ceil(K);
+
+            // 单列布局：位宽增加只增加行数
+            let circuit = RunningSumCircuit::<Fp, K>::new(Fp::zero(), num_bits);
+            let stats = CircuitStats::measure(&circuit).unwrap();
+            assert_eq!(stats.advice_columns, 1);
+            assert_eq!(stats.lookups, 2);
+            assert_eq!(stats.table_rows, 1 << K);
+
+            let max = two.pow_vartime([num_bits as u64]) - Fp::one();
+            assert_eq!(run(max, num_bits), Ok(()));
+            assert_eq!(run(Fp::zero(), num_bits), Ok(()));
+
+            // 2^num_bits 超出范围（最后一个窗口由短范围检查约束）
+            let overflow = two.pow_vartime([num_bits as u64]);
+            assert!(run(overflow, num_bits).is_err());
+            let overflow = two.pow_vartime([(num_windows * K) as u64]);
+            assert!(run(overflow, num_bits).is_err());
+            assert!(run(-Fp::one(), num_bits).is_err());
+
+            println!("{num_bits}位滑动和范围检查通过（{num_windows}个{K}位窗口）");
+        }
+    }
+
+    /// 复制一个cell后做短范围检查
+    #[derive(Default)]
+    struct ShortRangeCircuit {
+        value: Value<Fp>,
+        num_bits: usize,
+    }
+
+    impl Circuit<Fp> for ShortRangeCircuit {
+        type Config = (Column<Advice>, RunningSumConfig<Fp, 8>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                value: Value::unknown(),
+                num_bits: self.num_bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let input = meta.advice_column();
+            meta.enable_equality(input);
+            (input, RunningSumCircuit::<Fp, 8>::configure(meta))
+        }
+
+        fn synthesize(
+            &self,
+            (input, config): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load_table(&mut layouter)?;
+            let cell = layouter.assign_region(
+                || "load value",
+                |mut region| region.assign_advice(|| "value", input, 0, || self.value),
+            )?;
+            config.short_range_check(layouter.namespace(|| "short"), &cell, self.num_bits)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_short_range_check() {
+        // 同一张256项的字节表，检查3位和7位的值
+        for num_bits in [3, 7] {
+            let run = |value: u64| {
+                let circuit = ShortRangeCircuit {
+                    value: Value::known(Fp::from(value)),
+                    num_bits,
+                };
+                let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+                MockProver::run(k, &circuit, vec![]).unwrap().verify()
+            };
+            let bound = 1u64 << num_bits;
+            assert_eq!(run(0), Ok(()));
+            assert_eq!(run(bound - 1), Ok(()));
+            assert!(run(bound).is_err());
+            assert!(run(255).is_err());
+        }
+
+        // 20位 = 两个完整字节 + 一个4位窗口
+        let run = |value: u64| {
+            let circuit = RunningSumCircuit::<Fp, 8>::new(Fp::from(value), 20);
+            let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+            MockProver::run(k, &circuit, vec![]).unwrap().verify()
+        };
+        assert_eq!(run((1 << 20) - 1), Ok(()));
+        assert!(run(1 << 20).is_err());
+        assert!(run((1 << 24) - 1).is_err());
+
+        println!("短范围检查测试通过！");
+    }
+
+    #[test]
+    fn test_invalid_bit_widths_are_synthesis_errors() {
+        // 短范围检查要求 0 < num_bits < K
+        for num_bits in [0, 8, 9] {
+            let circuit = ShortRangeCircuit {
+                value: Value::known(Fp::zero()),
+                num_bits,
+            };
+            assert!(matches!(
+                MockProver::run(10, &circuit, vec![]),
+                Err(Error::Synthesis)
+            ));
+        }
+
+        // 完整范围检查要求 num_bits > 0，且窗口总位数小于域的位数
+        for num_bits in [0, 255, 256] {
+            let circuit = RunningSumCircuit::<Fp, 8>::new(Fp::zero(), num_bits);
+            assert!(matches!(
+                MockProver::run(10, &circuit, vec![]),
+                Err(Error::Synthesis)
+            ));
+        }
+
+        println!("非法位宽返回Synthesis错误测试通过！");
+    }
+
+    #[test]
+    fn test_decompose_windows() {
+        let value = Fp::from(0b1011_0110_1110u64);
+        assert_eq!(
+            decompose_windows(&value, 4, 3),
+            vec![0b1110, 0b0110, 0b1011]
+        );
+        assert_eq!(
+            decompose_windows(&value, 4, 4),
+            vec![0b1110, 0b0110, 0b1011, 0]
+        );
+    }
+}
@@ -2,31 +2,146 @@ use std::marker::PhantomData;
 
 use halo2_proofs::{circuit::*, pasta::group::ff::PrimeField, plonk::*};
 
+/// 整数区间 `[lo, hi]`（两端都包含）
+///
+/// table中存放的是平移后的 `0..=hi - lo`，查表时输入同样减去 `lo`：
+/// - 负数下界也能落进table（例如 `[-8, 7]` 存为 `0..=15`）
+/// - table始终包含0，selector关闭的行输入为0，不需要额外填充
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableRange {
+    lo: i64,
+    hi: i64,
+}
+
+impl TableRange {
+    /// `[lo, hi]`，两端都包含
+    pub fn inclusive(lo: i64, hi: i64) -> Self {
+        assert!(lo <= hi, "empty table range [{lo}, {hi}]");
+        Self { lo, hi }
+    }
+
+    /// `[lo, hi)`，不包含 `hi`
+    pub fn exclusive(lo: i64, hi: i64) -> Self {
+        assert!(lo < hi, "empty table range [{lo}, {hi})");
+        Self { lo, hi: hi - 1 }
+    }
+
+    /// `[0, size)`，与原来的 `0..RANGE` 相同
+    pub fn up_to(size: usize) -> Self {
+        Self::exclusive(0, size as i64)
+    }
+
+    /// 最小值（包含）
+    pub fn lo(&self) -> i64 {
+        self.lo
+    }
+
+    /// 最大值（包含）
+    pub fn hi(&self) -> i64 {
+        self.hi
+    }
+
+    /// table的行数，区间宽度超出 `usize` 时返回 `Error::Synthesis`（这样的table也无法加载）
+    pub fn len(&self) -> Result<usize, Error> {
+        self.hi
+            .checked_sub(self.lo)
+            .and_then(|width| usize::try_from(width).ok())
+            .and_then(|width| width.checked_add(1))
+            .ok_or(Error::Synthesis)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, Error> {
+        self.len().map(|len| len == 0)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+}
+
+/// 有符号整数在域中的表示，负数映射为 p - |v|
+pub fn field_from_i64<F: PrimeField>(value: i64) -> F {
+    let abs = F::from(value.unsigned_abs());
+    if value < 0 { -abs } else { abs }
+}
+
+/// 解析域元素：十进制或 `0x` 开头的十六进制，允许前导 `-` 表示取负
+///
+/// 逐位在域中累加，位数不受限制（超过模数的值按模约简）。
+/// 命令行参数和 `ValueSet::from_file` 共用这一个解析器。
+pub(crate) fn parse_field<F: PrimeField>(text: &str) -> Option<F> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let (digits, radix) = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (text, 10),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let value = digits.chars().try_fold(F::ZERO, |acc, c| {
+        let digit = c.to_digit(radix)?;
+        Some(acc * F::from(radix as u64) + F::from(digit as u64))
+    })?;
+    Some(if negative { -value } else { value })
+}
+
+/// 域元素小端表示的低64位，只用于计算witness，超出范围的部分由电路约束拒绝
+pub(crate) fn low_u64<F: PrimeField>(value: &F) -> u64 {
+    let repr = value.to_repr();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&repr.as_ref()[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// 2^bits，在域中逐次倍增计算，位数超过64也不会溢出
+pub fn two_pow<F: PrimeField>(bits: usize) -> F {
+    (0..bits).fold(F::ONE, |acc, _| acc.double())
+}
+
+/// 存放 `TableRange` 中所有值的单列lookup table
 #[derive(Debug, Clone)]
-pub(crate) struct LookUpTable<F: PrimeField, const RANGE: usize> {
-    pub(crate) table: TableColumn,
+pub struct LookUpTable<F: PrimeField> {
+    /// lookup参数中引用的table列
+    pub table: TableColumn,
+    range: TableRange,
     _maker: PhantomData<F>,
 }
 
-impl<F: PrimeField, const RANGE: usize> LookUpTable<F, RANGE> {
-    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+impl<F: PrimeField> LookUpTable<F> {
+    /// 分配一个新的table列，区间在配置时确定
+    pub fn configure(meta: &mut ConstraintSystem<F>, range: TableRange) -> Self {
         let table = meta.lookup_table_column();
         Self {
             table,
+            range,
             _maker: PhantomData,
         }
     }
 
+    pub fn range(&self) -> TableRange {
+        self.range
+    }
+
+    /// lookup的输入表达式：`selector · (value - lo)`
+    pub fn input(&self, selector: Expression<F>, value: Expression<F>) -> Expression<F> {
+        selector * (value - Expression::Constant(field_from_i64(self.range.lo)))
+    }
+
+    /// 填充table（每个电路只能加载一次）
     pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_table(
             || "load range lookup table",
             |mut table| {
-                for value in 0..RANGE {
+                for offset in 0..self.range.len()? {
                     table.assign_cell(
                         || "table cell",
                         self.table,
-                        value,
-                        || Value::known(F::from(value as u64)),
+                        offset,
+                        || Value::known(F::from(offset as u64)),
                     )?;
                 }
                 Ok(())
@@ -34,3 +149,46 @@ impl<F: PrimeField, const RANGE: usize> LookUpTable<F, RANGE> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::pasta::Fp;
+
+    #[test]
+    fn test_table_range_semantics() {
+        // exclusive 不包含上界，inclusive 两端都包含
+        assert_eq!(TableRange::exclusive(0, 16), TableRange::inclusive(0, 15));
+        assert_eq!(TableRange::up_to(16), TableRange::inclusive(0, 15));
+        assert_eq!(TableRange::up_to(16).len().unwrap(), 16);
+
+        let range = TableRange::inclusive(-8, 7);
+        assert_eq!(range.len().unwrap(), 16);
+        assert!(!range.is_empty().unwrap());
+
+        // 宽度超出i64的区间返回错误而不是溢出
+        let wide = TableRange::inclusive(i64::MIN, i64::MAX);
+        assert!(matches!(wide.len(), Err(Error::Synthesis)));
+        assert!(wide.is_empty().is_err());
+        assert!(range.contains(-8) && range.contains(7));
+        assert!(!range.contains(-9) && !range.contains(8));
+
+        assert_eq!(field_from_i64::<Fp>(-3) + Fp::from(3), Fp::zero());
+        assert_eq!(field_from_i64::<Fp>(5), Fp::from(5));
+
+        assert_eq!(parse_field::<Fp>(" 42 "), Some(Fp::from(42)));
+        assert_eq!(parse_field::<Fp>("0x2A"), Some(Fp::from(42)));
+        assert_eq!(parse_field::<Fp>("-0x2a"), Some(-Fp::from(42)));
+        // 超过128位的值按域运算
+        let two_pow_130 = (0..130).fold(Fp::one(), |acc, _| acc.double());
+        assert_eq!(
+            parse_field::<Fp>("1361129467683753853853498429727072845824"),
+            Some(two_pow_130)
+        );
+        for bad in ["", "-", "0x", "12a", "1.5", "--1"] {
+            assert_eq!(parse_field::<Fp>(bad), None, "{bad:?}");
+        }
+
+        println!("table区间语义测试通过！");
+    }
+}
@@ -0,0 +1,298 @@
+/// ==============================================
+/// 共享lookup table
+/// ==============================================
+///
+/// 每个芯片各自分配table列时，同一个电路里可能出现多个内容完全相同的 0..256 表，
+/// 每个都占一个fixed列，还要分别加载。`TableRegistry` 在configure阶段按区间去重，
+/// 相同区间只分配一列；synthesize阶段由 `TableLoader` 记录已加载的区间，每个table只加载一次。
+///
+/// 加载状态不放在registry（即Config）中：floor planner可能多次调用synthesize，
+/// 每次都从 `loader` / `load_all` 得到一个新的 `TableLoader`，从未加载开始。
+/// 共享的table只能通过 `TableLoader` 加载，不要再调用芯片自己的 `load_byte_table`。
+use std::collections::HashSet;
+
+use halo2_proofs::{
+    circuit::Layouter,
+    pasta::group::ff::PrimeField,
+    plonk::{ConstraintSystem, Error},
+};
+
+use super::table::{LookUpTable, TableRange};
+
+#[derive(Debug, Clone)]
+pub struct TableRegistry<F: PrimeField> {
+    tables: Vec<LookUpTable<F>>,
+}
+
+impl<F: PrimeField> Default for TableRegistry<F> {
+    fn default() -> Self {
+        Self { tables: Vec::new() }
+    }
+}
+
+impl<F: PrimeField> TableRegistry<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回存放 `range` 的table，已有相同区间时复用，否则分配新列
+    pub fn range_table(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        range: TableRange,
+    ) -> LookUpTable<F> {
+        if let Some(table) = self.tables.iter().find(|t| t.range() == range) {
+            return table.clone();
+        }
+
+        let table = LookUpTable::configure(meta, range);
+        self.tables.push(table.clone());
+        table
+    }
+
+    /// 0..256 的字节表
+    pub fn byte_table(&mut self, meta: &mut ConstraintSystem<F>) -> LookUpTable<F> {
+        self.range_table(meta, TableRange::up_to(256))
+    }
+
+    /// 去重后table的个数
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// 开始一次synthesize中的加载，还没有加载任何table
+    pub fn loader(&self) -> TableLoader<'_, F> {
+        TableLoader {
+            registry: self,
+            loaded: HashSet::new(),
+        }
+    }
+
+    /// 加载所有注册过的table，返回的 `TableLoader` 记录了已加载的区间
+    pub fn load_all(&self, layouter: &mut impl Layouter<F>) -> Result<TableLoader<'_, F>, Error> {
+        let mut loader = self.loader();
+        loader.load_all(layouter)?;
+        Ok(loader)
+    }
+}
+
+/// 一次synthesize中已加载的table
+///
+/// 同一区间重复加载什么也不做，所以 `load` 和 `load_all` 可以任意组合
+#[derive(Debug)]
+pub struct TableLoader<'a, F: PrimeField> {
+    registry: &'a TableRegistry<F>,
+    loaded: HashSet<TableRange>,
+}
+
+impl<F: PrimeField> TableLoader<'_, F> {
+    /// 加载 `range` 对应的table，已经加载过时什么也不做，
+    /// `range` 没有注册时返回 `Error::Synthesis`
+    pub fn load(
+        &mut self,
+        layouter: &mut impl Layouter<F>,
+        range: TableRange,
+    ) -> Result<(), Error> {
+        let table = self
+            .registry
+            .tables
+            .iter()
+            .find(|t| t.range() == range)
+            .ok_or(Error::Synthesis)?;
+
+        if self.loaded.insert(range) {
+            table.load(layouter)?;
+        }
+        Ok(())
+    }
+
+    /// 加载所有尚未加载的table
+    pub fn load_all(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        for table in self.registry.tables.iter() {
+            self.load(layouter, table.range())?;
+        }
+        Ok(())
+    }
+
+    pub fn is_loaded(&self, range: TableRange) -> bool {
+        self.loaded.contains(&range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lookup::{
+            large_range_analysis::BitDecompositionConfig, rangecheck_lookup::RangeConfig,
+            running_sum::RunningSumConfig,
+        },
+        stats::CircuitStats,
+    };
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Assigned, Circuit},
+    };
+
+    /// 两个字节分解、一个字节范围检查和一个8位窗口的滑动和共用一张 0..256 表
+    #[derive(Default)]
+    struct SharedTablesCircuit {
+        words: [Value<u32>; 2],
+        bytes: [Value<Assigned<Fp>>; 2],
+        running: Value<Fp>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct SharedTablesConfig {
+        tables: TableRegistry<Fp>,
+        words: [BitDecompositionConfig<Fp>; 2],
+        bytes: RangeConfig<Fp, 2>,
+        running: RunningSumConfig<Fp, 8>,
+    }
+
+    impl Circuit<Fp> for SharedTablesCircuit {
+        type Config = SharedTablesConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let mut tables = TableRegistry::new();
+
+            let words = [(); 2].map(|_| {
+                let value = meta.advice_column();
+                let bytes = [(); 4].map(|_| meta.advice_column());
+                let byte_table = tables.byte_table(meta);
+                BitDecompositionConfig::configure(meta, value, bytes, byte_table)
+            });
+
+            let advice = meta.advice_column();
+            let byte_table = tables.byte_table(meta);
+            let bytes = RangeConfig::configure_with_table(meta, advice, byte_table);
+
+            let z = meta.advice_column();
+            let shift = meta.fixed_column();
+            let window_table = tables.range_table(meta, TableRange::up_to(1 << 8));
+            let running = RunningSumConfig::configure(meta, z, window_table, shift);
+
+            SharedTablesConfig {
+                tables,
+                words,
+                bytes,
+                running,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.tables.load_all(&mut layouter)?;
+
+            for (i, (word, &value)) in config.words.iter().zip(self.words.iter()).enumerate() {
+                word.assign_and_decompose(layouter.namespace(|| format!("word {i}")), value)?;
+            }
+            config
+                .bytes
+                .assign(layouter.namespace(|| "bytes"), self.bytes)?;
+            config
+                .running
+                .witness_check(layouter.namespace(|| "running sum"), self.running, 20)?;
+            Ok(())
+        }
+    }
+
+    fn shared_circuit(byte: u64) -> SharedTablesCircuit {
+        SharedTablesCircuit {
+            words: [Value::known(0x12345678), Value::known(u32::MAX)],
+            bytes: [
+                Value::known(Assigned::from(Fp::zero())),
+                Value::known(Assigned::from(Fp::from(byte))),
+            ],
+            running: Value::known(Fp::from(0xFFFFF)),
+        }
+    }
+
+    #[test]
+    fn test_shared_tables() {
+        let circuit = shared_circuit(255);
+        let stats = CircuitStats::measure(&circuit).unwrap();
+
+        // 只有一张table列加上滑动和的shift列，table只占256行
+        assert_eq!(stats.fixed_columns, 2);
+        assert_eq!(stats.table_rows, 256);
+
+        let prover = MockProver::run(stats.minimal_k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let prover = MockProver::run(stats.minimal_k, &shared_circuit(256), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+
+        println!("共享lookup table测试通过！");
+    }
+
+    /// 注册字节表和 0..16 表，先单独加载 `range`，再加载全部
+    struct LoadRangeCircuit {
+        range: TableRange,
+    }
+
+    impl Circuit<Fp> for LoadRangeCircuit {
+        type Config = TableRegistry<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { range: self.range }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let mut tables = TableRegistry::new();
+            tables.byte_table(meta);
+            tables.range_table(meta, TableRange::up_to(16));
+            tables
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let mut loader = config.loader();
+            loader.load(&mut layouter, self.range)?;
+            // 已加载的区间不会再次分配table
+            loader.load(&mut layouter, self.range)?;
+            loader.load_all(&mut layouter)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_load_and_load_all() {
+        let circuit = LoadRangeCircuit {
+            range: TableRange::up_to(256),
+        };
+        assert_eq!(
+            MockProver::run(9, &circuit, vec![]).unwrap().verify(),
+            Ok(())
+        );
+
+        // 没有注册的区间返回错误而不是panic
+        let circuit = LoadRangeCircuit {
+            range: TableRange::up_to(32),
+        };
+        assert!(matches!(
+            MockProver::run(9, &circuit, vec![]),
+            Err(Error::Synthesis)
+        ));
+
+        println!("table加载去重测试通过！");
+    }
+}
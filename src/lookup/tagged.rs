@@ -0,0 +1,289 @@
+/// ==============================================
+/// 带标签的table打包
+/// ==============================================
+///
+/// 每个不同的小table都要占一整列：同时用到 0..16 的范围表和 0..256 的字节表，
+/// 就要付出两列。这里把多个逻辑table放进同一对 `(tag, value)` table列，
+/// 每个逻辑table分配一个标签，lookup查询 `(tag, value)`，只有同标签的行能匹配。
+///
+/// 标签0保留给 `(0, 0)` 这一行：selector关闭时输入为 `(0, 0)`，
+/// 不会意外匹配任何逻辑table中的0。
+///
+/// | tag | value |
+/// |-----|-------|
+/// |  0  |   0   |
+/// |  1  |   0   |
+/// |  1  |  ...  |
+/// |  1  |  15   |
+/// |  2  |   0   |
+/// |  2  |  ...  |
+/// |  2  |  255  |
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    pasta::group::ff::PrimeField,
+    plonk::*,
+    poly::Rotation,
+};
+
+use super::{
+    bitwise::TupleTable,
+    membership::ValueSet,
+    table::{TableRange, field_from_i64},
+};
+
+/// 逻辑table的标签（从1开始）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableTag(u64);
+
+impl TableTag {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// 逻辑table的内容
+#[derive(Debug, Clone)]
+enum TaggedContents<F: PrimeField> {
+    Range(TableRange),
+    Set(ValueSet<F>),
+}
+
+/// 共用一对table列的多个逻辑table
+#[derive(Debug, Clone)]
+pub struct TaggedTable<F: PrimeField> {
+    pub table: TupleTable<F, 2>,
+    entries: Vec<TaggedContents<F>>,
+}
+
+impl<F: PrimeField> TaggedTable<F> {
+    /// 分配 `(tag, value)` 两列，逻辑table之后逐个添加
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            table: TupleTable::configure(meta),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 添加 `range` 中的所有整数，返回其标签
+    pub fn add_range(&mut self, range: TableRange) -> TableTag {
+        self.entries.push(TaggedContents::Range(range));
+        TableTag(self.entries.len() as u64)
+    }
+
+    /// 添加任意集合，返回其标签
+    pub fn add_set(&mut self, set: ValueSet<F>) -> TableTag {
+        self.entries.push(TaggedContents::Set(set));
+        TableTag(self.entries.len() as u64)
+    }
+
+    /// 逻辑table的个数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// lookup参数：`(selector · tag, selector · value)`
+    pub fn lookup(
+        &self,
+        tag: TableTag,
+        selector: Expression<F>,
+        value: Expression<F>,
+    ) -> Vec<(Expression<F>, TableColumn)> {
+        assert!(
+            tag.0 >= 1 && tag.0 as usize <= self.entries.len(),
+            "unknown table tag {}",
+            tag.0
+        );
+        self.table
+            .lookup(selector, [Expression::Constant(F::from(tag.0)), value])
+    }
+
+    /// 加载保留行 `(0, 0)` 和所有逻辑table
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let mut rows = vec![[F::ZERO, F::ZERO]];
+        for (i, entry) in self.entries.iter().enumerate() {
+            let tag = F::from(i as u64 + 1);
+            match entry {
+                TaggedContents::Range(range) => {
+                    rows.extend((range.lo()..=range.hi()).map(|v| [tag, field_from_i64(v)]));
+                }
+                TaggedContents::Set(set) => {
+                    rows.extend(set.values().iter().map(|&v| [tag, v]));
+                }
+            }
+        }
+        self.table.load(layouter, &rows)
+    }
+}
+
+/// 在某一个逻辑table中查找值
+#[derive(Debug, Clone)]
+pub struct TaggedLookupConfig<F: PrimeField> {
+    value: Column<Advice>,
+    q_lookup: Selector,
+    tag: TableTag,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> TaggedLookupConfig<F> {
+    /// 多个标签可以共用同一个 `value` 列，各自使用自己的selector
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        table: &TaggedTable<F>,
+        tag: TableTag,
+    ) -> Self {
+        let q_lookup = meta.complex_selector();
+        meta.enable_equality(value);
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let v = meta.query_advice(value, Rotation::cur());
+            table.lookup(tag, q, v)
+        });
+
+        TaggedLookupConfig {
+            value,
+            q_lookup,
+            tag,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn tag(&self) -> TableTag {
+        self.tag
+    }
+
+    /// 分配新值并检查它在该逻辑table中
+    pub fn witness_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || format!("tagged lookup {}", self.tag.0),
+            |mut region| {
+                self.q_lookup.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", self.value, 0, || value)
+            },
+        )
+    }
+
+    /// 检查已分配的cell在该逻辑table中
+    pub fn copy_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || format!("tagged lookup {} of assigned cell", self.tag.0),
+            |mut region| {
+                self.q_lookup.enable(&mut region, 0)?;
+                cell.copy_advice(|| "value", &mut region, self.value, 0)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::CircuitStats;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+    };
+
+    /// 4位范围、字节范围和一个小集合共用一对table列
+    #[derive(Default)]
+    struct PackedCircuit {
+        nibble: Value<Fp>,
+        byte: Value<Fp>,
+        member: Value<Fp>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct PackedConfig {
+        table: TaggedTable<Fp>,
+        nibble: TaggedLookupConfig<Fp>,
+        byte: TaggedLookupConfig<Fp>,
+        member: TaggedLookupConfig<Fp>,
+    }
+
+    impl Circuit<Fp> for PackedCircuit {
+        type Config = PackedConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let mut table = TaggedTable::configure(meta);
+            let nibble_tag = table.add_range(TableRange::up_to(16));
+            let byte_tag = table.add_range(TableRange::up_to(256));
+            let set_tag = table.add_set(ValueSet::from_u64s(&[3, 1000, 1 << 40]));
+
+            let value = meta.advice_column();
+            PackedConfig {
+                nibble: TaggedLookupConfig::configure(meta, value, &table, nibble_tag),
+                byte: TaggedLookupConfig::configure(meta, value, &table, byte_tag),
+                member: TaggedLookupConfig::configure(meta, value, &table, set_tag),
+                table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.table.load(&mut layouter)?;
+            config
+                .nibble
+                .witness_check(layouter.namespace(|| "nibble"), self.nibble)?;
+            config
+                .byte
+                .witness_check(layouter.namespace(|| "byte"), self.byte)?;
+            config
+                .member
+                .witness_check(layouter.namespace(|| "member"), self.member)?;
+            Ok(())
+        }
+    }
+
+    fn run(nibble: u64, byte: u64, member: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = PackedCircuit {
+            nibble: Value::known(Fp::from(nibble)),
+            byte: Value::known(Fp::from(byte)),
+            member: Value::known(Fp::from(member)),
+        };
+        let k = CircuitStats::measure(&circuit).unwrap().minimal_k;
+        MockProver::run(k, &circuit, vec![]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_tagged_tables() {
+        // 三个逻辑table只占两列：保留行 + 16 + 256 + 3 行
+        let stats = CircuitStats::measure(&PackedCircuit::default()).unwrap();
+        assert_eq!(stats.fixed_columns, 2);
+        assert_eq!(stats.table_rows, 1 + 16 + 256 + 3);
+
+        assert_eq!(run(15, 255, 1 << 40), Ok(()));
+        assert_eq!(run(0, 0, 3), Ok(()));
+        // 200在字节表中，但不在4位表中：标签不同不能匹配
+        assert!(run(200, 255, 3).is_err());
+        assert!(run(0, 256, 3).is_err());
+        // 0只在保留行中有标签0，不是集合成员
+        assert!(run(0, 0, 0).is_err());
+        assert!(run(0, 0, 15).is_err());
+
+        println!("带标签的table打包测试通过！");
+    }
+}
@@ -1,3 +1,348 @@
-fn main() {
-    println!("Hello, world!");
+//! halo2-demo 命令行工具
+//!
+//! 不写Rust测试也能运行crate中的电路：
+//! ```text
+//! halo2-demo list
+//! halo2-demo mock    <circuit> [key=value ...] [options]
+//! halo2-demo prove   <circuit> [key=value ...] [options] --out proof.bin
+//! halo2-demo verify  <circuit> --proof proof.bin [key=value ...] [--instance V ...]
+//! halo2-demo inspect <proof-file>
+//! ```
+use std::{env, fs, process::ExitCode};
+
+use halo2_demo::{
+    lookup::large_range_analysis::{
+        compare_schemes, reports_to_csv, reports_to_json, sample_values,
+    },
+    proof::{
+        cache::{ParamsAndKeys, ParamsCache},
+        digest,
+        envelope::{EnvelopeError, ProofEnvelope},
+    },
+    registry::{CircuitId, CircuitVisitor, Inputs, Mock, Prove, parse_field},
+};
+use halo2_proofs::{pasta::Fp, plonk::Circuit};
+
+const USAGE: &str = "\
+usage: halo2-demo <command> [args]
+
+commands:
+  list                               list all circuits and their inputs
+  stats   <circuit>                  print columns, gates, rows and minimal k
+  mock    <circuit> [key=value ...]  run MockProver
+  prove   <circuit> [key=value ...]  create a proof envelope
+  verify  <circuit> --proof <file>   verify a proof envelope (pass the same circuit
+                                     parameters as `prove`, e.g. constant=3)
+  inspect <proof-file>               print proof envelope metadata
+  compare                            compare the two large range-check schemes
+
+options:
+  --input-file <file>   read `key = value` inputs from a file
+  --instance <value>    public instance value (repeatable, defaults to the computed output)
+  --k <k>               circuit size (defaults to the circuit's minimal k)
+  --out <file>          proof output path for `prove` (default: <circuit>.proof)
+  --proof <file>        proof path for `verify`
+  --cache-dir <dir>     params cache directory (default: .halo2-cache)
+  --count <n>           number of values checked by `compare` (default: 1)
+  --format <json|csv>   report format for `compare` (default: json)";
+
+/// 解析后的命令行参数
+#[derive(Default)]
+struct Args {
+    positional: Vec<String>,
+    inputs: Inputs,
+    instances: Vec<Fp>,
+    k: Option<u32>,
+    out: Option<String>,
+    proof: Option<String>,
+    cache_dir: Option<String>,
+    count: Option<usize>,
+    format: Option<String>,
+}
+
+impl Args {
+    fn parse(raw: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = Args::default();
+        let mut raw = raw.into_iter();
+
+        while let Some(arg) = raw.next() {
+            let mut value = |name: &str| {
+                raw.next()
+                    .ok_or_else(|| format!("missing value for {name}"))
+            };
+
+            match arg.as_str() {
+                "--input-file" => {
+                    let path = value("--input-file")?;
+                    let contents = fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+                    args.inputs
+                        .parse_file(&contents)
+                        .map_err(|e| e.to_string())?;
+                }
+                "--instance" => {
+                    let text = value("--instance")?;
+                    let v =
+                        parse_field(&text).ok_or_else(|| format!("invalid instance `{text}`"))?;
+                    args.instances.push(v);
+                }
+                "--k" => {
+                    let text = value("--k")?;
+                    args.k = Some(text.parse().map_err(|_| format!("invalid k `{text}`"))?);
+                }
+                "--out" => args.out = Some(value("--out")?),
+                "--proof" => args.proof = Some(value("--proof")?),
+                "--cache-dir" => args.cache_dir = Some(value("--cache-dir")?),
+                "--count" => {
+                    let text = value("--count")?;
+                    args.count = Some(
+                        text.parse()
+                            .map_err(|_| format!("invalid count `{text}`"))?,
+                    );
+                }
+                "--format" => args.format = Some(value("--format")?),
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+                _ if arg.contains('=') => args
+                    .inputs
+                    .parse_assignment(&arg)
+                    .map_err(|e| e.to_string())?,
+                _ => args.positional.push(arg),
+            }
+        }
+
+        Ok(args)
+    }
+
+    fn circuit(&self) -> Result<CircuitId, String> {
+        let name = self
+            .positional
+            .first()
+            .ok_or_else(|| "missing circuit name (see `list`)".to_string())?;
+        CircuitId::from_name(name).map_err(|e| e.to_string())
+    }
+
+    /// 命令行给出的k优先，否则使用测量得到的最小k
+    fn k(&self, id: CircuitId) -> Result<u32, String> {
+        match self.k {
+            Some(k) => Ok(k),
+            None => id
+                .stats()
+                .map(|stats| stats.minimal_k)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    fn cache(&self) -> ParamsCache {
+        ParamsCache::new(self.cache_dir.as_deref().unwrap_or(".halo2-cache"))
+    }
+
+    /// 命令行给出的公开值优先，否则根据私有输入计算
+    fn instances(&self, id: CircuitId) -> Result<Vec<Vec<Fp>>, String> {
+        if self.instances.is_empty() {
+            return id.public_outputs(&self.inputs).map_err(|e| e.to_string());
+        }
+        if id.num_instance_columns() == 0 {
+            return Err(format!("circuit `{id}` has no public instances"));
+        }
+        Ok(vec![self.instances.clone()])
+    }
+}
+
+/// 从缓存取出参数并生成密钥
+struct LoadKeys<'a> {
+    cache: &'a ParamsCache,
+    name: &'a str,
+    k: u32,
+}
+
+impl CircuitVisitor for LoadKeys<'_> {
+    type Output = Result<ParamsAndKeys, String>;
+
+    fn visit<C: Circuit<Fp>>(self, circuit: C) -> Self::Output {
+        self.cache
+            .params_and_keys(self.name, self.k, &circuit)
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn list() -> Result<(), String> {
+    for id in CircuitId::ALL {
+        let k = id.stats().map_err(|e| e.to_string())?.minimal_k;
+        println!("{:<18} k={:<3} {}", id.name(), k, id.description());
+        println!("{:<18} inputs: {}", "", id.input_names().join(", "));
+        if !id.parameter_names().is_empty() {
+            let names = id.parameter_names().join(", ");
+            println!("{:<18} verify also needs: {names}", "");
+        }
+    }
+    Ok(())
+}
+
+fn stats(args: &Args) -> Result<(), String> {
+    let id = args.circuit()?;
+    let stats = id.stats().map_err(|e| e.to_string())?;
+    println!("{id}: {}", id.description());
+    println!("{stats}");
+    Ok(())
+}
+
+fn mock(args: &Args) -> Result<(), String> {
+    let id = args.circuit()?;
+    let k = args.k(id)?;
+    let instances = args.instances(id)?;
+
+    let mock = Mock {
+        k,
+        instances: &instances,
+    };
+    match id
+        .with_circuit(&args.inputs, mock)
+        .map_err(|e| e.to_string())?
+    {
+        Ok(()) => {
+            println!("{id}: constraints satisfied (k = {k})");
+            Ok(())
+        }
+        Err(failures) => {
+            for failure in &failures {
+                eprintln!("{failure}");
+            }
+            Err(format!("{id}: {} constraint failure(s)", failures.len()))
+        }
+    }
+}
+
+fn prove(args: &Args) -> Result<(), String> {
+    let id = args.circuit()?;
+    let k = args.k(id)?;
+    let instances = args.instances(id)?;
+    let cache = args.cache();
+
+    let keys = id
+        .with_key_circuit(
+            &args.inputs,
+            LoadKeys {
+                cache: &cache,
+                name: id.name(),
+                k,
+            },
+        )
+        .map_err(|e| e.to_string())??;
+
+    let prove = Prove {
+        params: &keys.params,
+        pk: &keys.pk,
+        instances: &instances,
+    };
+    let proof = id
+        .with_circuit(&args.inputs, prove)
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("proving failed: {e}"))?;
+
+    let columns: Vec<&[Fp]> = instances.iter().map(|c| c.as_slice()).collect();
+    let envelope = ProofEnvelope::new(id.name(), k, keys.pk.get_vk(), &columns, proof);
+    let bytes = envelope.encode().map_err(|e| e.to_string())?;
+
+    let out = args.out.clone().unwrap_or_else(|| format!("{id}.proof"));
+    fs::write(&out, &bytes).map_err(|e| format!("{out}: {e}"))?;
+    println!("{id}: wrote {} byte proof envelope to {out}", bytes.len());
+    Ok(())
+}
+
+fn verify(args: &Args) -> Result<(), String> {
+    let id = args.circuit()?;
+    let path = args
+        .proof
+        .as_deref()
+        .ok_or_else(|| "missing --proof <file>".to_string())?;
+    let bytes = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+    let envelope = ProofEnvelope::decode(&bytes).map_err(|e| e.to_string())?;
+
+    // 验证方自己声明期望的公开值时，必须与证明中携带的一致
+    if !args.instances.is_empty() && envelope.instances != vec![args.instances.clone()] {
+        return Err("public instances in the proof do not match --instance".to_string());
+    }
+
+    // k来自验证方期望的电路（`--k` 只是显式覆盖），不能取自待验证的证明本身
+    let k = args.k(id)?;
+    if envelope.k != k {
+        return Err(EnvelopeError::KMismatch {
+            expected: k,
+            found: envelope.k,
+        }
+        .to_string());
+    }
+
+    // 密钥由电路参数（例如 `constant=`）决定，验证方必须给出与证明方相同的值
+    let cache = args.cache();
+    let keys = id
+        .with_key_circuit(
+            &args.inputs,
+            LoadKeys {
+                cache: &cache,
+                name: id.name(),
+                k,
+            },
+        )
+        .map_err(|e| e.to_string())??;
+
+    envelope
+        .verify(id.name(), k, &keys.params, keys.pk.get_vk())
+        .map_err(|e| e.to_string())?;
+    println!("{id}: proof verified");
+    Ok(())
+}
+
+fn inspect(args: &Args) -> Result<(), String> {
+    let path = args
+        .positional
+        .first()
+        .ok_or_else(|| "missing proof file".to_string())?;
+    let bytes = fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+    let envelope = ProofEnvelope::decode(&bytes).map_err(|e| e.to_string())?;
+
+    println!("circuit:    {}", envelope.circuit);
+    println!("k:          {}", envelope.k);
+    println!("vk digest:  {}", digest::to_hex(&envelope.vk_digest));
+    for (i, column) in envelope.instances.iter().enumerate() {
+        println!("instance {i}: {column:?}");
+    }
+    println!("proof size: {} bytes", envelope.proof.len());
+    Ok(())
+}
+
+fn compare(args: &Args) -> Result<(), String> {
+    let count = args.count.unwrap_or(1);
+    let values = sample_values(count).map_err(|_| format!("count {count} exceeds {}", u32::MAX))?;
+    let reports = compare_schemes(&values).map_err(|e| e.to_string())?;
+
+    match args.format.as_deref().unwrap_or("json") {
+        "json" => print!("{}", reports_to_json(&reports)),
+        "csv" => print!("{}", reports_to_csv(&reports)),
+        other => return Err(format!("unknown report format `{other}`")),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let mut raw = env::args().skip(1);
+    let command = raw.next();
+
+    let result = Args::parse(raw).and_then(|args| match command.as_deref() {
+        Some("list") => list(),
+        Some("stats") => stats(&args),
+        Some("mock") => mock(&args),
+        Some("prove") => prove(&args),
+        Some("verify") => verify(&args),
+        Some("inspect") => inspect(&args),
+        Some("compare") => compare(&args),
+        _ => Err(USAGE.to_string()),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,37 @@
+//! 常用芯片、配置和已分配值的统一导出
+//!
+//! ```ignore
+//! use halo2_demo::prelude::*;
+//! ```
+pub use crate::{
+    basic::{
+        basic_chip::{SquareSumChip, SquareSumCircuit, SquareSumConfig},
+        basic_middle::{Number, OptimizedCircuit, OptimizedFieldChip, OptimizedFieldConfig},
+        boolean::{Bool, BoolChip, BoolConfig},
+        division::{DivChip, DivConfig},
+        instructions::{
+            ArithmeticInstructions, PublicOutputInstructions, square_sum, square_sum_with_product,
+        },
+        is_zero::{IsZeroChip, IsZeroConfig},
+        multi_chip_design::{
+            AddChip, AddConfig, MulChip, MulConfig, MultiChip, MultiChipCircuit, MultiChipConfig,
+            SquareChip, SquareConfig,
+        },
+    },
+    lookup::{
+        binary_rows::{BinaryRowRangeCircuit, BinaryRowRangeConfig},
+        bitwise::{BitwiseConfig, BitwiseOp, BitwiseTable, TupleTable},
+        canonical::{CanonicalBits, CanonicalDecompositionCircuit, CanonicalDecompositionConfig},
+        comparison::{Bounded, ComparisonConfig},
+        large_range_analysis::{
+            BinaryRangeCircuit, BinaryRangeConfig, BitDecompositionCircuit, BitDecompositionConfig,
+        },
+        membership::{MembershipCircuit, MembershipConfig, MembershipTable, ValueSet},
+        rangecheck_lookup::{ACell, RangeConfig},
+        running_sum::{RunningSumCircuit, RunningSumConfig},
+        table::{LookUpTable, TableRange},
+        table_registry::{TableLoader, TableRegistry},
+        tagged::{TableTag, TaggedLookupConfig, TaggedTable},
+    },
+    stats::CircuitStats,
+};
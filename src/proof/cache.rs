@@ -0,0 +1,338 @@
+/// ==============================================
+/// 参数磁盘缓存
+/// ==============================================
+///
+/// 目录结构:
+/// ```text
+/// <dir>/
+/// ├── params-k10.bin                        # Params<EqAffine>，只依赖k，所有电路共享
+/// └── bit-decomposition-k10-<cs摘要>.keys    # vk清单：电路名、k、cs摘要、vk摘要
+/// ```
+///
+/// halo2_proofs 0.3 只提供 `Params` 的读写，没有 `VerifyingKey`/`ProvingKey` 的序列化接口，
+/// 所以这里只缓存参数：密钥每次都基于缓存的参数重新生成（keygen是确定性的），
+/// 缓存省掉的是参数生成。`CacheStatus` 只描述参数是否命中，
+/// 重新生成的vk与清单中记录的摘要比较，结果见 `VkStatus`。
+///
+/// 清单文件名包含约束系统摘要，`configure` 的输出一旦改变就会落到新的文件名上，
+/// 旧条目自然失效。
+use std::{
+    fmt, fs,
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{Circuit, Error, ProvingKey},
+    poly::commitment::Params,
+};
+
+use super::{digest, pipeline};
+
+/// 缓存错误
+#[derive(Debug)]
+pub enum CacheError {
+    /// 读写缓存目录失败
+    Io(io::Error),
+    /// 密钥生成失败
+    Plonk(Error),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Io(e) => write!(f, "cache io error: {e}"),
+            CacheError::Plonk(e) => write!(f, "keygen error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl From<io::Error> for CacheError {
+    fn from(e: io::Error) -> Self {
+        CacheError::Io(e)
+    }
+}
+
+impl From<Error> for CacheError {
+    fn from(e: Error) -> Self {
+        CacheError::Plonk(e)
+    }
+}
+
+/// 参数缓存查询结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// 参数从磁盘读取
+    Hit,
+    /// 参数不存在或无法读取（文件被截断、损坏），已重新生成并写入
+    Miss,
+}
+
+/// 重新生成的vk与清单的比较结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VkStatus {
+    /// 没有对应的清单（新电路、新k或 `configure` 已改变），已写入
+    New,
+    /// vk摘要与清单一致
+    Unchanged,
+    /// 清单存在但vk摘要不一致，已覆盖
+    Changed,
+}
+
+/// 缓存的参数和基于它生成的密钥
+pub struct ParamsAndKeys {
+    pub params: Params<EqAffine>,
+    pub pk: ProvingKey<EqAffine>,
+    pub params_status: CacheStatus,
+    pub vk_status: VkStatus,
+}
+
+/// vk清单
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KeyManifest {
+    circuit: String,
+    k: u32,
+    cs_digest: digest::Digest,
+    vk_digest: digest::Digest,
+}
+
+impl KeyManifest {
+    fn encode(&self) -> String {
+        format!(
+            "circuit={}\nk={}\ncs_digest={}\nvk_digest={}\n",
+            self.circuit,
+            self.k,
+            digest::to_hex(&self.cs_digest),
+            digest::to_hex(&self.vk_digest)
+        )
+    }
+
+    /// 解析失败返回None，调用方按缓存未命中处理
+    fn decode(text: &str) -> Option<Self> {
+        let mut circuit = None;
+        let mut k = None;
+        let mut cs_digest = None;
+        let mut vk_digest = None;
+
+        for line in text.lines() {
+            let (key, value) = line.split_once('=')?;
+            match key {
+                "circuit" => circuit = Some(value.to_string()),
+                "k" => k = value.parse().ok(),
+                "cs_digest" => cs_digest = digest::from_hex(value),
+                "vk_digest" => vk_digest = digest::from_hex(value),
+                _ => return None,
+            }
+        }
+
+        Some(KeyManifest {
+            circuit: circuit?,
+            k: k?,
+            cs_digest: cs_digest?,
+            vk_digest: vk_digest?,
+        })
+    }
+}
+
+/// 参数磁盘缓存
+#[derive(Debug, Clone)]
+pub struct ParamsCache {
+    dir: PathBuf,
+}
+
+impl ParamsCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ParamsCache { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn params_path(&self, k: u32) -> PathBuf {
+        self.dir.join(format!("params-k{k}.bin"))
+    }
+
+    fn manifest_path(&self, name: &str, k: u32, cs_digest: &digest::Digest) -> PathBuf {
+        let short = digest::to_hex(&cs_digest[..8]);
+        self.dir.join(format!("{name}-k{k}-{short}.keys"))
+    }
+
+    /// 读取参数，不存在或读取失败时重新生成并写入缓存
+    pub fn params(&self, k: u32) -> Result<(Params<EqAffine>, CacheStatus), CacheError> {
+        let path = self.params_path(k);
+        // 写入是原子的，读取失败只能来自外部的截断或损坏，与文件不存在一样处理
+        let cached = fs::File::open(&path).and_then(|file| Params::read(&mut BufReader::new(file)));
+        if let Ok(params) = cached {
+            return Ok((params, CacheStatus::Hit));
+        }
+
+        let params = Params::new(k);
+        let mut bytes = vec![];
+        params.write(&mut bytes)?;
+        self.write_atomic(&path, &bytes)?;
+        Ok((params, CacheStatus::Miss))
+    }
+
+    /// 读取或生成参数，并基于它生成密钥（密钥总是重新生成）
+    pub fn params_and_keys<C: Circuit<Fp>>(
+        &self,
+        name: &str,
+        k: u32,
+        circuit: &C,
+    ) -> Result<ParamsAndKeys, CacheError> {
+        fs::create_dir_all(&self.dir)?;
+
+        let (params, params_status) = self.params(k)?;
+        let cs_digest = digest::constraint_system_digest::<C>();
+        let manifest_path = self.manifest_path(name, k, &cs_digest);
+
+        let cached = fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|text| KeyManifest::decode(&text))
+            .filter(|m| m.circuit == name && m.k == k && m.cs_digest == cs_digest);
+
+        let pk = pipeline::keygen(&params, circuit)?;
+        let vk_digest = digest::verifying_key_digest(pk.get_vk());
+
+        let vk_status = match cached {
+            Some(m) if m.vk_digest == vk_digest => VkStatus::Unchanged,
+            Some(_) => VkStatus::Changed,
+            None => VkStatus::New,
+        };
+
+        if vk_status != VkStatus::Unchanged {
+            let manifest = KeyManifest {
+                circuit: name.to_string(),
+                k,
+                cs_digest,
+                vk_digest,
+            };
+            self.write_atomic(&manifest_path, manifest.encode().as_bytes())?;
+        }
+
+        Ok(ParamsAndKeys {
+            params,
+            pk,
+            params_status,
+            vk_status,
+        })
+    }
+
+    /// 先写临时文件再重命名，避免并发运行时读到半个文件
+    fn write_atomic(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        {
+            let mut writer = BufWriter::new(fs::File::create(&tmp)?);
+            writer.write_all(bytes)?;
+            writer.flush()?;
+        }
+        fs::rename(&tmp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic::basic_chip::SquareSumCircuit;
+    use halo2_proofs::circuit::Value;
+
+    fn temp_cache(test: &str) -> ParamsCache {
+        let dir =
+            std::env::temp_dir().join(format!("halo2-demo-cache-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ParamsCache::new(dir)
+    }
+
+    #[test]
+    fn test_params_cache_and_vk_manifest() {
+        let k = 4;
+        let cache = temp_cache("hit");
+        let a = Fp::from(3);
+        let b = Fp::from(4);
+        let c = a * a + b * b;
+        let circuit = SquareSumCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+
+        // 第一次：生成参数并写入，记录vk摘要
+        let first = cache.params_and_keys("square-sum", k, &circuit).unwrap();
+        assert_eq!(first.params_status, CacheStatus::Miss);
+        assert_eq!(first.vk_status, VkStatus::New);
+
+        // 第二次：参数命中缓存，重新生成的vk与清单一致，密钥可以直接用于证明
+        let second = cache.params_and_keys("square-sum", k, &circuit).unwrap();
+        assert_eq!(second.params_status, CacheStatus::Hit);
+        assert_eq!(second.vk_status, VkStatus::Unchanged);
+
+        let proof = pipeline::prove(&second.params, &second.pk, circuit, &[&[c]]).unwrap();
+        assert!(pipeline::verify(&first.params, first.pk.get_vk(), &[&[c]], &proof).is_ok());
+
+        // 篡改清单中的vk摘要后被发现并覆盖
+        let cs_digest = digest::constraint_system_digest::<SquareSumCircuit<Fp>>();
+        let manifest_path = cache.manifest_path("square-sum", k, &cs_digest);
+        let text = fs::read_to_string(&manifest_path).unwrap();
+        let mut manifest = KeyManifest::decode(&text).unwrap();
+        manifest.vk_digest = [0u8; digest::DIGEST_LEN];
+        fs::write(&manifest_path, manifest.encode()).unwrap();
+
+        let circuit = SquareSumCircuit::<Fp>::default();
+        let third = cache.params_and_keys("square-sum", k, &circuit).unwrap();
+        assert_eq!(third.params_status, CacheStatus::Hit);
+        assert_eq!(third.vk_status, VkStatus::Changed);
+
+        let fourth = cache.params_and_keys("square-sum", k, &circuit).unwrap();
+        assert_eq!(fourth.vk_status, VkStatus::Unchanged);
+
+        // 不同的k对应不同的缓存条目
+        let other_k = cache
+            .params_and_keys("square-sum", k + 1, &circuit)
+            .unwrap();
+        assert_eq!(other_k.params_status, CacheStatus::Miss);
+        assert_eq!(other_k.vk_status, VkStatus::New);
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_params_are_regenerated() {
+        let k = 4;
+        let cache = temp_cache("corrupted");
+        let (_, status) = cache.params(k).unwrap();
+        assert_eq!(status, CacheStatus::Miss);
+
+        // 截断缓存的参数文件：按未命中处理，重新生成并覆盖
+        let path = cache.params_path(k);
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+        let (params, status) = cache.params(k).unwrap();
+        assert_eq!(status, CacheStatus::Miss);
+        let mut rewritten = vec![];
+        params.write(&mut rewritten).unwrap();
+        assert_eq!(rewritten, bytes);
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        let (_, status) = cache.params(k).unwrap();
+        assert_eq!(status, CacheStatus::Hit);
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_key_manifest_roundtrip() {
+        let manifest = KeyManifest {
+            circuit: "square-sum".to_string(),
+            k: 4,
+            cs_digest: [1u8; digest::DIGEST_LEN],
+            vk_digest: [2u8; digest::DIGEST_LEN],
+        };
+        assert_eq!(KeyManifest::decode(&manifest.encode()), Some(manifest));
+        assert_eq!(KeyManifest::decode("circuit=square-sum\nk=4\n"), None);
+    }
+}
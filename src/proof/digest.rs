@@ -0,0 +1,57 @@
+/// ==============================================
+/// 电路结构摘要
+/// ==============================================
+///
+/// 用Blake2b对 `configure` 的输出和验证密钥做指纹，
+/// 用于缓存失效判断和证明文件的自描述。
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{Circuit, ConstraintSystem, VerifyingKey},
+};
+
+/// 摘要长度（字节）
+pub const DIGEST_LEN: usize = 32;
+
+pub type Digest = [u8; DIGEST_LEN];
+
+fn hash(personal: &[u8], data: &[u8]) -> Digest {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(DIGEST_LEN)
+        .personal(personal)
+        .hash(data);
+
+    let mut out = [0u8; DIGEST_LEN];
+    out.copy_from_slice(hash.as_bytes());
+    out
+}
+
+/// 约束系统摘要：列、门、lookup、permutation都会影响结果，
+/// 只要 `configure` 的输出发生变化，摘要就会改变。
+pub fn constraint_system_digest<C: Circuit<Fp>>() -> Digest {
+    let mut meta = ConstraintSystem::<Fp>::default();
+    C::configure(&mut meta);
+    hash(b"halo2demo_cs", format!("{meta:?}").as_bytes())
+}
+
+/// 验证密钥摘要：在约束系统之外还包含domain和fixed列承诺
+pub fn verifying_key_digest(vk: &VerifyingKey<EqAffine>) -> Digest {
+    hash(b"halo2demo_vk", format!("{:?}", vk.pinned()).as_bytes())
+}
+
+/// 十六进制编码（小写）
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// 十六进制解码，长度或字符非法时返回None
+pub fn from_hex(hex: &str) -> Option<Digest> {
+    if hex.len() != DIGEST_LEN * 2 {
+        return None;
+    }
+
+    let mut out = [0u8; DIGEST_LEN];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    Some(out)
+}
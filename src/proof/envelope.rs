@@ -0,0 +1,394 @@
+/// ==============================================
+/// 自描述证明文件格式（证明信封）
+/// ==============================================
+///
+/// 所有整数均为小端序:
+/// | 字段          | 长度            | 说明                                  |
+/// |---------------|-----------------|---------------------------------------|
+/// | magic         | 4               | `b"H2PF"`                             |
+/// | version       | 2               | 格式版本，目前为1                     |
+/// | circuit id    | 1 + n           | 长度前缀的UTF-8电路标识               |
+/// | k             | 4               | 电路规模参数                          |
+/// | vk digest     | 32              | 验证密钥摘要                          |
+/// | instances     | 4 + Σ(4 + 32·m) | instance列数，每列长度 + 规范编码的域元素 |
+/// | proof         | 4 + n           | 长度前缀的transcript字节              |
+///
+/// 验证方在调用 `verify_proof` 之前先比对电路标识、k和vk摘要，
+/// 把"用A电路的证明冒充B电路"之类的错误挡在外面。
+use std::fmt;
+
+use halo2_proofs::{
+    pasta::{EqAffine, Fp, group::ff::PrimeField},
+    plonk::{Error, VerifyingKey},
+    poly::commitment::Params,
+};
+
+use super::{digest, pipeline};
+
+/// 文件头魔数
+pub const MAGIC: [u8; 4] = *b"H2PF";
+/// 当前格式版本
+pub const VERSION: u16 = 1;
+
+/// 信封解析和校验错误
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// 魔数不匹配，不是证明信封
+    BadMagic,
+    /// 不支持的格式版本
+    UnsupportedVersion(u16),
+    /// 数据提前结束
+    Truncated,
+    /// 解析完成后仍有多余字节
+    TrailingBytes(usize),
+    /// 电路标识不是合法的UTF-8或超过255字节
+    InvalidCircuitId,
+    /// 编码时长度超出u32长度前缀的范围
+    TooLong { field: &'static str, len: usize },
+    /// instance值不是规范编码的域元素
+    NonCanonicalInstance { column: usize, row: usize },
+    /// 电路标识与验证方期望的不一致
+    CircuitMismatch { expected: String, found: String },
+    /// k与验证方期望的不一致
+    KMismatch { expected: u32, found: u32 },
+    /// vk摘要与验证方持有的验证密钥不一致
+    VkDigestMismatch,
+    /// 证明本身验证失败
+    Verification(Error),
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::BadMagic => write!(f, "not a proof envelope (bad magic)"),
+            EnvelopeError::UnsupportedVersion(v) => write!(f, "unsupported envelope version {v}"),
+            EnvelopeError::Truncated => write!(f, "envelope is truncated"),
+            EnvelopeError::TrailingBytes(n) => write!(f, "{n} trailing bytes after envelope"),
+            EnvelopeError::InvalidCircuitId => write!(f, "invalid circuit identifier"),
+            EnvelopeError::TooLong { field, len } => {
+                write!(f, "{field} length {len} does not fit a u32 length prefix")
+            }
+            EnvelopeError::NonCanonicalInstance { column, row } => {
+                write!(
+                    f,
+                    "non-canonical instance value at column {column}, row {row}"
+                )
+            }
+            EnvelopeError::CircuitMismatch { expected, found } => {
+                write!(f, "proof is for circuit `{found}`, expected `{expected}`")
+            }
+            EnvelopeError::KMismatch { expected, found } => {
+                write!(f, "proof is for k = {found}, expected k = {expected}")
+            }
+            EnvelopeError::VkDigestMismatch => write!(f, "verifying key digest mismatch"),
+            EnvelopeError::Verification(e) => write!(f, "proof verification failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+/// 证明信封
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofEnvelope {
+    pub circuit: String,
+    pub k: u32,
+    pub vk_digest: digest::Digest,
+    pub instances: Vec<Vec<Fp>>,
+    pub proof: Vec<u8>,
+}
+
+impl ProofEnvelope {
+    pub fn new(
+        circuit: impl Into<String>,
+        k: u32,
+        vk: &VerifyingKey<EqAffine>,
+        instances: &[&[Fp]],
+        proof: Vec<u8>,
+    ) -> Self {
+        ProofEnvelope {
+            circuit: circuit.into(),
+            k,
+            vk_digest: digest::verifying_key_digest(vk),
+            instances: instances.iter().map(|column| column.to_vec()).collect(),
+            proof,
+        }
+    }
+
+    /// 编码为字节
+    pub fn encode(&self) -> Result<Vec<u8>, EnvelopeError> {
+        let circuit = self.circuit.as_bytes();
+        let circuit_len =
+            u8::try_from(circuit.len()).map_err(|_| EnvelopeError::InvalidCircuitId)?;
+
+        let mut out = vec![];
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.push(circuit_len);
+        out.extend_from_slice(circuit);
+        out.extend_from_slice(&self.k.to_le_bytes());
+        out.extend_from_slice(&self.vk_digest);
+
+        out.extend_from_slice(&length_prefix("instance columns", self.instances.len())?);
+        for column in &self.instances {
+            out.extend_from_slice(&length_prefix("instance column", column.len())?);
+            for value in column {
+                out.extend_from_slice(&value.to_repr());
+            }
+        }
+
+        out.extend_from_slice(&length_prefix("proof", self.proof.len())?);
+        out.extend_from_slice(&self.proof);
+        Ok(out)
+    }
+
+    /// 从字节解码，任何格式问题都返回错误而不是尽力解析
+    pub fn decode(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(EnvelopeError::BadMagic);
+        }
+
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(version));
+        }
+
+        let circuit_len = reader.take(1)?[0] as usize;
+        let circuit = std::str::from_utf8(reader.take(circuit_len)?)
+            .map_err(|_| EnvelopeError::InvalidCircuitId)?
+            .to_string();
+
+        let k = reader.u32()?;
+
+        let mut vk_digest = [0u8; digest::DIGEST_LEN];
+        vk_digest.copy_from_slice(reader.take(digest::DIGEST_LEN)?);
+
+        let num_columns = reader.u32()? as usize;
+        let mut instances = vec![];
+        for column in 0..num_columns {
+            let len = reader.u32()? as usize;
+            let mut values = vec![];
+            for row in 0..len {
+                let mut repr = <Fp as PrimeField>::Repr::default();
+                repr.copy_from_slice(reader.take(32)?);
+                let value = Option::<Fp>::from(Fp::from_repr(repr))
+                    .ok_or(EnvelopeError::NonCanonicalInstance { column, row })?;
+                values.push(value);
+            }
+            instances.push(values);
+        }
+
+        let proof_len = reader.u32()? as usize;
+        let proof = reader.take(proof_len)?.to_vec();
+
+        if !reader.bytes.is_empty() {
+            return Err(EnvelopeError::TrailingBytes(reader.bytes.len()));
+        }
+
+        Ok(ProofEnvelope {
+            circuit,
+            k,
+            vk_digest,
+            instances,
+            proof,
+        })
+    }
+
+    /// 检查信封是否属于期望的电路和验证密钥，不运行 `verify_proof`
+    pub fn check(
+        &self,
+        circuit: &str,
+        k: u32,
+        vk: &VerifyingKey<EqAffine>,
+    ) -> Result<(), EnvelopeError> {
+        if self.circuit != circuit {
+            return Err(EnvelopeError::CircuitMismatch {
+                expected: circuit.to_string(),
+                found: self.circuit.clone(),
+            });
+        }
+        if self.k != k {
+            return Err(EnvelopeError::KMismatch {
+                expected: k,
+                found: self.k,
+            });
+        }
+        if self.vk_digest != digest::verifying_key_digest(vk) {
+            return Err(EnvelopeError::VkDigestMismatch);
+        }
+        Ok(())
+    }
+
+    /// 先检查元数据，再验证证明
+    pub fn verify(
+        &self,
+        circuit: &str,
+        k: u32,
+        params: &Params<EqAffine>,
+        vk: &VerifyingKey<EqAffine>,
+    ) -> Result<(), EnvelopeError> {
+        self.check(circuit, k, vk)?;
+
+        let instances: Vec<&[Fp]> = self.instances.iter().map(|c| c.as_slice()).collect();
+        pipeline::verify(params, vk, &instances, &self.proof).map_err(EnvelopeError::Verification)
+    }
+}
+
+/// u32小端长度前缀，超出范围时返回错误而不是截断
+fn length_prefix(field: &'static str, len: usize) -> Result<[u8; 4], EnvelopeError> {
+    u32::try_from(len)
+        .map(u32::to_le_bytes)
+        .map_err(|_| EnvelopeError::TooLong { field, len })
+}
+
+/// 顺序读取字节的小工具
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], EnvelopeError> {
+        if self.bytes.len() < n {
+            return Err(EnvelopeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, EnvelopeError> {
+        let mut buf = [0u8; 2];
+        buf.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn u32(&mut self) -> Result<u32, EnvelopeError> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic::basic_chip::SquareSumCircuit;
+    use halo2_proofs::circuit::Value;
+
+    #[test]
+    fn test_proof_envelope_roundtrip_and_validation() {
+        let k = 4;
+        let a = Fp::from(3);
+        let b = Fp::from(4);
+        let c = a * a + b * b;
+        let circuit = SquareSumCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+
+        let (params, pk) = pipeline::setup(k, &circuit).unwrap();
+        let proof = pipeline::prove(&params, &pk, circuit, &[&[c]]).unwrap();
+        let envelope = ProofEnvelope::new("square-sum", k, pk.get_vk(), &[&[c]], proof);
+
+        // 编码/解码往返
+        let bytes = envelope.encode().unwrap();
+        let decoded = ProofEnvelope::decode(&bytes).unwrap();
+        assert_eq!(decoded, envelope);
+        assert!(
+            decoded
+                .verify("square-sum", k, &params, pk.get_vk())
+                .is_ok()
+        );
+
+        // 冒充其他电路的证明，在验证之前就被拒绝
+        assert!(matches!(
+            decoded.verify("optimized", k, &params, pk.get_vk()),
+            Err(EnvelopeError::CircuitMismatch { .. })
+        ));
+        assert!(matches!(
+            decoded.check("square-sum", k + 1, pk.get_vk()),
+            Err(EnvelopeError::KMismatch { .. })
+        ));
+
+        // vk摘要不一致
+        let mut other = decoded.clone();
+        other.vk_digest = [0u8; digest::DIGEST_LEN];
+        assert!(matches!(
+            other.check("square-sum", k, pk.get_vk()),
+            Err(EnvelopeError::VkDigestMismatch)
+        ));
+
+        // 公开值被篡改：元数据一致，但证明验证失败
+        let mut other = decoded.clone();
+        other.instances[0][0] += Fp::one();
+        assert!(matches!(
+            other.verify("square-sum", k, &params, pk.get_vk()),
+            Err(EnvelopeError::Verification(_))
+        ));
+    }
+
+    #[test]
+    fn test_proof_envelope_rejects_malformed_bytes() {
+        let envelope = ProofEnvelope {
+            circuit: "square-sum".to_string(),
+            k: 4,
+            vk_digest: [7u8; digest::DIGEST_LEN],
+            instances: vec![vec![Fp::from(25)]],
+            proof: vec![1, 2, 3],
+        };
+        let bytes = envelope.encode().unwrap();
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert!(matches!(
+            ProofEnvelope::decode(&bad),
+            Err(EnvelopeError::BadMagic)
+        ));
+
+        let mut bad = bytes.clone();
+        bad[4] = 2;
+        assert!(matches!(
+            ProofEnvelope::decode(&bad),
+            Err(EnvelopeError::UnsupportedVersion(2))
+        ));
+
+        assert!(matches!(
+            ProofEnvelope::decode(&bytes[..bytes.len() - 1]),
+            Err(EnvelopeError::Truncated)
+        ));
+
+        let mut bad = bytes.clone();
+        bad.push(0);
+        assert!(matches!(
+            ProofEnvelope::decode(&bad),
+            Err(EnvelopeError::TrailingBytes(1))
+        ));
+
+        // 把instance值改成 ≥ 模数的编码
+        let instance_offset = 4 + 2 + 1 + envelope.circuit.len() + 4 + digest::DIGEST_LEN + 4 + 4;
+        let mut bad = bytes.clone();
+        bad[instance_offset..instance_offset + 32].copy_from_slice(&[0xff; 32]);
+        assert!(matches!(
+            ProofEnvelope::decode(&bad),
+            Err(EnvelopeError::NonCanonicalInstance { column: 0, row: 0 })
+        ));
+
+        let long_name = ProofEnvelope {
+            circuit: "x".repeat(256),
+            ..envelope
+        };
+        assert!(matches!(
+            long_name.encode(),
+            Err(EnvelopeError::InvalidCircuitId)
+        ));
+
+        // 超过u32的长度不会被截断
+        assert_eq!(length_prefix("proof", 3).unwrap(), [3, 0, 0, 0]);
+        assert!(matches!(
+            length_prefix("proof", u32::MAX as usize + 1),
+            Err(EnvelopeError::TooLong { field: "proof", .. })
+        ));
+    }
+}
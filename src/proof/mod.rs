@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod digest;
+pub mod envelope;
+pub mod pipeline;
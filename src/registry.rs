@@ -0,0 +1,527 @@
+/// ==============================================
+/// 电路注册表：按名称统一访问crate中的所有电路
+/// ==============================================
+///
+/// 命令行工具和其他不想直接写Rust测试的调用方通过这里：
+/// 1. 从 `key=value` 形式的输入构造电路
+/// 2. 计算期望的公开输出
+/// 3. 运行MockProver / 生成密钥 / 生成证明
+use std::{collections::BTreeMap, fmt};
+
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    pasta::{EqAffine, Fp},
+    plonk::{Assigned, Circuit, Error, ProvingKey},
+    poly::commitment::Params,
+};
+
+use crate::{
+    basic::{
+        basic_chip::SquareSumCircuit, basic_middle::OptimizedCircuit,
+        multi_chip_design::MultiChipCircuit,
+    },
+    lookup::{
+        large_range_analysis::{BinaryRangeCircuit, BitDecompositionCircuit},
+        rangecheck_lookup::MyCircuit,
+        table,
+    },
+    proof::pipeline,
+    stats::CircuitStats,
+};
+
+/// range-lookup电路的查找表大小和待检查值个数
+pub const RANGE_LOOKUP_RANGE: usize = 16;
+pub const RANGE_LOOKUP_NUM: usize = 3;
+
+type RangeLookupCircuit = MyCircuit<Fp, RANGE_LOOKUP_RANGE, RANGE_LOOKUP_NUM>;
+
+/// 输入解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// 未知的电路名称
+    UnknownCircuit(String),
+    /// 缺少必需的输入
+    Missing(String),
+    /// 输入值无法解析
+    Invalid { key: String, value: String },
+    /// 输入行不是 `key=value` 形式
+    Malformed(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::UnknownCircuit(name) => write!(f, "unknown circuit `{name}`"),
+            InputError::Missing(key) => write!(f, "missing input `{key}`"),
+            InputError::Invalid { key, value } => write!(f, "invalid value `{value}` for `{key}`"),
+            InputError::Malformed(line) => write!(f, "expected `key=value`, got `{line}`"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// 解析域元素：十进制或 `0x` 开头的十六进制，允许前导 `-` 表示取负
+///
+/// 与 `ValueSet::from_file` 使用同一个解析器
+pub fn parse_field(text: &str) -> Option<Fp> {
+    table::parse_field(text)
+}
+
+/// 解析无符号整数：十进制或 `0x` 开头的十六进制
+fn parse_u64(text: &str) -> Option<u64> {
+    let text = text.trim();
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// 电路的私有输入，键值对形式
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inputs {
+    values: BTreeMap<String, String>,
+}
+
+impl Inputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// 解析一条 `key=value` 赋值
+    pub fn parse_assignment(&mut self, assignment: &str) -> Result<(), InputError> {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| InputError::Malformed(assignment.to_string()))?;
+        self.insert(key.trim(), value.trim());
+        Ok(())
+    }
+
+    /// 解析输入文件：每行一条 `key = value`，`#` 之后为注释
+    pub fn parse_file(&mut self, contents: &str) -> Result<(), InputError> {
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if !line.is_empty() {
+                self.parse_assignment(line)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn raw(&self, key: &str) -> Result<&str, InputError> {
+        self.values
+            .get(key)
+            .map(|v| v.as_str())
+            .ok_or_else(|| InputError::Missing(key.to_string()))
+    }
+
+    fn invalid(key: &str, value: &str) -> InputError {
+        InputError::Invalid {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn field(&self, key: &str) -> Result<Fp, InputError> {
+        let raw = self.raw(key)?;
+        parse_field(raw).ok_or_else(|| Self::invalid(key, raw))
+    }
+
+    pub fn u64(&self, key: &str) -> Result<u64, InputError> {
+        let raw = self.raw(key)?;
+        parse_u64(raw).ok_or_else(|| Self::invalid(key, raw))
+    }
+
+    pub fn u32(&self, key: &str) -> Result<u32, InputError> {
+        let raw = self.raw(key)?;
+        parse_u64(raw)
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| Self::invalid(key, raw))
+    }
+}
+
+/// 对具体电路类型执行操作
+///
+/// 注册表按名称分发到不同的电路类型，调用方通过实现这个trait
+/// 写一次泛型逻辑，就能作用于所有电路。
+pub trait CircuitVisitor {
+    type Output;
+
+    fn visit<C: Circuit<Fp>>(self, circuit: C) -> Self::Output;
+}
+
+/// crate中所有可按名称访问的电路
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitId {
+    SquareSum,
+    Optimized,
+    MultiChip,
+    RangeLookup,
+    BitDecomposition,
+    BinaryRange,
+}
+
+impl CircuitId {
+    pub const ALL: [CircuitId; 6] = [
+        CircuitId::SquareSum,
+        CircuitId::Optimized,
+        CircuitId::MultiChip,
+        CircuitId::RangeLookup,
+        CircuitId::BitDecomposition,
+        CircuitId::BinaryRange,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CircuitId::SquareSum => "square-sum",
+            CircuitId::Optimized => "optimized",
+            CircuitId::MultiChip => "multi-chip",
+            CircuitId::RangeLookup => "range-lookup",
+            CircuitId::BitDecomposition => "bit-decomposition",
+            CircuitId::BinaryRange => "binary-range",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, InputError> {
+        CircuitId::ALL
+            .into_iter()
+            .find(|id| id.name() == name)
+            .ok_or_else(|| InputError::UnknownCircuit(name.to_string()))
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            CircuitId::SquareSum => "a² + b² = out (basic_chip)",
+            CircuitId::Optimized => "a² + b² + a×b×constant = out (basic_middle)",
+            CircuitId::MultiChip => "a² + b² + a×b×constant = out (multi_chip_design)",
+            CircuitId::RangeLookup => "v0, v1, v2 ∈ [0, 16) via lookup (rangecheck_lookup)",
+            CircuitId::BitDecomposition => "value < 2^32 via byte lookups (large_range_analysis)",
+            CircuitId::BinaryRange => "value < 2^32 via binary constraints (large_range_analysis)",
+        }
+    }
+
+    /// 电路统计（列、门、行占用和最小k）
+    pub fn stats(&self) -> Result<CircuitStats, Error> {
+        self.with_empty_circuit(Measure)
+    }
+
+    /// 决定电路结构的输入：写进fixed列，因此也会写进密钥，验证时必须给出相同的值
+    pub fn parameter_names(&self) -> &'static [&'static str] {
+        match self {
+            CircuitId::Optimized | CircuitId::MultiChip => &["constant"],
+            CircuitId::SquareSum
+            | CircuitId::RangeLookup
+            | CircuitId::BitDecomposition
+            | CircuitId::BinaryRange => &[],
+        }
+    }
+
+    /// 私有输入的名称
+    pub fn input_names(&self) -> &'static [&'static str] {
+        match self {
+            CircuitId::SquareSum => &["a", "b"],
+            CircuitId::Optimized | CircuitId::MultiChip => &["a", "b", "constant"],
+            CircuitId::RangeLookup => &["v0", "v1", "v2"],
+            CircuitId::BitDecomposition | CircuitId::BinaryRange => &["value"],
+        }
+    }
+
+    /// instance列个数
+    pub fn num_instance_columns(&self) -> usize {
+        match self {
+            CircuitId::SquareSum | CircuitId::Optimized | CircuitId::MultiChip => 1,
+            CircuitId::RangeLookup | CircuitId::BitDecomposition | CircuitId::BinaryRange => 0,
+        }
+    }
+
+    /// 根据私有输入计算期望的公开输出（每个instance列一个Vec）
+    pub fn public_outputs(&self, inputs: &Inputs) -> Result<Vec<Vec<Fp>>, InputError> {
+        match self {
+            CircuitId::SquareSum => {
+                let a = inputs.field("a")?;
+                let b = inputs.field("b")?;
+                Ok(vec![vec![a * a + b * b]])
+            }
+            CircuitId::Optimized | CircuitId::MultiChip => {
+                let a = inputs.field("a")?;
+                let b = inputs.field("b")?;
+                let constant = inputs.field("constant")?;
+                Ok(vec![vec![a * a + b * b + a * b * constant]])
+            }
+            CircuitId::RangeLookup | CircuitId::BitDecomposition | CircuitId::BinaryRange => {
+                Ok(vec![])
+            }
+        }
+    }
+
+    /// 用私有输入构造电路并交给visitor
+    pub fn with_circuit<V: CircuitVisitor>(
+        &self,
+        inputs: &Inputs,
+        visitor: V,
+    ) -> Result<V::Output, InputError> {
+        Ok(match self {
+            CircuitId::SquareSum => visitor.visit(SquareSumCircuit {
+                a: Value::known(inputs.field("a")?),
+                b: Value::known(inputs.field("b")?),
+            }),
+            CircuitId::Optimized => visitor.visit(OptimizedCircuit {
+                constant: inputs.field("constant")?,
+                a: Value::known(inputs.field("a")?),
+                b: Value::known(inputs.field("b")?),
+            }),
+            CircuitId::MultiChip => visitor.visit(MultiChipCircuit {
+                constant: inputs.field("constant")?,
+                a: Value::known(inputs.field("a")?),
+                b: Value::known(inputs.field("b")?),
+            }),
+            CircuitId::RangeLookup => {
+                let mut value = [Value::unknown(); RANGE_LOOKUP_NUM];
+                for (cell, name) in value.iter_mut().zip(self.input_names()) {
+                    *cell = Value::known(Assigned::from(Fp::from(inputs.u64(name)?)));
+                }
+                visitor.visit(RangeLookupCircuit { value })
+            }
+            CircuitId::BitDecomposition => {
+                visitor.visit(BitDecompositionCircuit::<Fp>::new(inputs.u32("value")?))
+            }
+            CircuitId::BinaryRange => {
+                visitor.visit(BinaryRangeCircuit::<Fp>::new(inputs.u32("value")?))
+            }
+        })
+    }
+
+    /// 用 `parameter_names` 中的输入构造不含witness的电路并交给visitor（用于密钥生成）
+    ///
+    /// 只需要电路参数，不需要私有输入，证明方和验证方得到相同的密钥
+    pub fn with_key_circuit<V: CircuitVisitor>(
+        &self,
+        inputs: &Inputs,
+        visitor: V,
+    ) -> Result<V::Output, InputError> {
+        Ok(match self {
+            CircuitId::Optimized => visitor.visit(OptimizedCircuit {
+                constant: inputs.field("constant")?,
+                ..OptimizedCircuit::<Fp>::default()
+            }),
+            CircuitId::MultiChip => visitor.visit(MultiChipCircuit {
+                constant: inputs.field("constant")?,
+                ..MultiChipCircuit::<Fp>::default()
+            }),
+            CircuitId::SquareSum
+            | CircuitId::RangeLookup
+            | CircuitId::BitDecomposition
+            | CircuitId::BinaryRange => self.with_empty_circuit(visitor),
+        })
+    }
+
+    /// 构造不含witness、参数取默认值的电路并交给visitor（只用于统计布局）
+    pub fn with_empty_circuit<V: CircuitVisitor>(&self, visitor: V) -> V::Output {
+        match self {
+            CircuitId::SquareSum => visitor.visit(SquareSumCircuit::<Fp>::default()),
+            CircuitId::Optimized => visitor.visit(OptimizedCircuit::<Fp>::default()),
+            CircuitId::MultiChip => visitor.visit(MultiChipCircuit::<Fp>::default()),
+            CircuitId::RangeLookup => visitor.visit(RangeLookupCircuit::default()),
+            CircuitId::BitDecomposition => visitor.visit(BitDecompositionCircuit::<Fp>::default()),
+            CircuitId::BinaryRange => visitor.visit(BinaryRangeCircuit::<Fp>::default()),
+        }
+    }
+}
+
+impl fmt::Display for CircuitId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 测量电路统计
+pub struct Measure;
+
+impl CircuitVisitor for Measure {
+    type Output = Result<CircuitStats, Error>;
+
+    fn visit<C: Circuit<Fp>>(self, circuit: C) -> Self::Output {
+        CircuitStats::measure(&circuit)
+    }
+}
+
+/// 运行MockProver，失败时返回每条约束失败的描述
+pub struct Mock<'a> {
+    pub k: u32,
+    pub instances: &'a [Vec<Fp>],
+}
+
+impl CircuitVisitor for Mock<'_> {
+    type Output = Result<(), Vec<String>>;
+
+    fn visit<C: Circuit<Fp>>(self, circuit: C) -> Self::Output {
+        let prover = MockProver::run(self.k, &circuit, self.instances.to_vec())
+            .map_err(|e| vec![e.to_string()])?;
+        prover
+            .verify()
+            .map_err(|failures| failures.iter().map(|f| f.to_string()).collect())
+    }
+}
+
+/// 生成证明
+pub struct Prove<'a> {
+    pub params: &'a Params<EqAffine>,
+    pub pk: &'a ProvingKey<EqAffine>,
+    pub instances: &'a [Vec<Fp>],
+}
+
+impl CircuitVisitor for Prove<'_> {
+    type Output = Result<Vec<u8>, Error>;
+
+    fn visit<C: Circuit<Fp>>(self, circuit: C) -> Self::Output {
+        let instances: Vec<&[Fp]> = self.instances.iter().map(|c| c.as_slice()).collect();
+        pipeline::prove(self.params, self.pk, circuit, &instances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(assignments: &[&str]) -> Inputs {
+        let mut inputs = Inputs::new();
+        for a in assignments {
+            inputs.parse_assignment(a).unwrap();
+        }
+        inputs
+    }
+
+    #[test]
+    fn test_registry_mock_all_circuits() {
+        let cases: [(CircuitId, &[&str]); 6] = [
+            (CircuitId::SquareSum, &["a=3", "b=4"]),
+            (CircuitId::Optimized, &["a=4", "b=5", "constant=3"]),
+            (CircuitId::MultiChip, &["a=4", "b=5", "constant=3"]),
+            (CircuitId::RangeLookup, &["v0=0", "v1=7", "v2=15"]),
+            (CircuitId::BitDecomposition, &["value=0x12345678"]),
+            (CircuitId::BinaryRange, &["value=0xFFFFFFFF"]),
+        ];
+
+        for (id, assignments) in cases {
+            assert_eq!(CircuitId::from_name(id.name()), Ok(id));
+
+            let inputs = inputs(assignments);
+            let instances = id.public_outputs(&inputs).unwrap();
+            assert_eq!(instances.len(), id.num_instance_columns());
+
+            let mock = Mock {
+                k: id.stats().unwrap().minimal_k,
+                instances: &instances,
+            };
+            assert_eq!(id.with_circuit(&inputs, mock).unwrap(), Ok(()), "{id}");
+        }
+
+        // 超出范围的值在MockProver中失败
+        let bad = inputs(&["v0=0", "v1=18", "v2=1"]);
+        let mock = Mock {
+            k: CircuitId::RangeLookup.stats().unwrap().minimal_k,
+            instances: &[],
+        };
+        assert!(
+            CircuitId::RangeLookup
+                .with_circuit(&bad, mock)
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    /// 生成参数和证明密钥
+    struct Setup {
+        k: u32,
+    }
+
+    impl CircuitVisitor for Setup {
+        type Output = Result<(Params<EqAffine>, ProvingKey<EqAffine>), Error>;
+
+        fn visit<C: Circuit<Fp>>(self, circuit: C) -> Self::Output {
+            pipeline::setup(self.k, &circuit)
+        }
+    }
+
+    #[test]
+    fn test_registry_prove_and_verify_with_constant() {
+        use crate::proof::envelope::{EnvelopeError, ProofEnvelope};
+
+        for id in [CircuitId::Optimized, CircuitId::MultiChip] {
+            let k = id.stats().unwrap().minimal_k;
+            let prover_inputs = inputs(&["a=4", "b=5", "constant=3"]);
+            let instances = id.public_outputs(&prover_inputs).unwrap();
+            let columns: Vec<&[Fp]> = instances.iter().map(|c| c.as_slice()).collect();
+
+            // 证明方：密钥来自电路参数，证明来自完整输入
+            let (params, pk) = id
+                .with_key_circuit(&prover_inputs, Setup { k })
+                .unwrap()
+                .unwrap();
+            let prove = Prove {
+                params: &params,
+                pk: &pk,
+                instances: &instances,
+            };
+            let proof = id.with_circuit(&prover_inputs, prove).unwrap().unwrap();
+            let envelope = ProofEnvelope::new(id.name(), k, pk.get_vk(), &columns, proof);
+
+            // 验证方只知道 constant=3，独立生成的密钥可以验证
+            let (params, pk) = id
+                .with_key_circuit(&inputs(&["constant=3"]), Setup { k })
+                .unwrap()
+                .unwrap();
+            assert!(
+                envelope.verify(id.name(), k, &params, pk.get_vk()).is_ok(),
+                "{id}"
+            );
+
+            // 不同的constant得到不同的密钥
+            let (params, pk) = id
+                .with_key_circuit(&inputs(&["constant=0"]), Setup { k })
+                .unwrap()
+                .unwrap();
+            assert!(matches!(
+                envelope.verify(id.name(), k, &params, pk.get_vk()),
+                Err(EnvelopeError::VkDigestMismatch)
+            ));
+
+            // 验证方必须给出constant
+            assert!(matches!(
+                id.with_key_circuit(&Inputs::new(), Setup { k }),
+                Err(InputError::Missing(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_registry_inputs() {
+        let mut parsed = Inputs::new();
+        parsed
+            .parse_file("# square-sum inputs\na = 3\n\nb = 0x4 # hex\n")
+            .unwrap();
+        assert_eq!(parsed, inputs(&["a=3", "b=0x4"]));
+        assert_eq!(parsed.field("b"), Ok(Fp::from(4)));
+
+        assert_eq!(parse_field("-1"), Some(-Fp::one()));
+        assert_eq!(parse_field("abc"), None);
+        assert_eq!(
+            CircuitId::from_name("nope"),
+            Err(InputError::UnknownCircuit("nope".to_string()))
+        );
+        assert_eq!(
+            CircuitId::SquareSum.public_outputs(&inputs(&["a=3"])),
+            Err(InputError::Missing("b".to_string()))
+        );
+        assert!(matches!(
+            inputs(&["value=0x100000000"]).u32("value"),
+            Err(InputError::Invalid { .. })
+        ));
+        assert!(Inputs::new().parse_assignment("a3").is_err());
+    }
+}
@@ -0,0 +1,309 @@
+/// ==============================================
+/// 电路统计与最小k计算
+/// ==============================================
+///
+/// 测试里手写的k（"稍微增大以容纳更多行"）容易偏大或偏小，这里直接测量：
+/// 1. 从 `configure` 的约束系统读取列数、门、lookup和最大次数
+/// 2. 用电路自己的FloorPlanner跑一遍 `synthesize`，记录region、table和instance占用的行
+/// 3. 加上blinding行，得到能放下电路的最小k
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::Value,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+
+/// 电路统计结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub advice_columns: usize,
+    /// fixed列数（包含lookup table列，不包含选择器）
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    /// lookup参数个数
+    pub lookups: usize,
+    pub gates: usize,
+    /// 所有门中的多项式约束总数
+    pub constraints: usize,
+    /// 约束系统的最大次数（门、lookup和permutation中的最大值）
+    pub max_degree: usize,
+    /// `assign_region` / `assign_table` 调用次数
+    pub regions: usize,
+    /// region占用的行数（advice、选择器、非table的fixed）
+    pub region_rows: usize,
+    /// lookup table占用的行数
+    pub table_rows: usize,
+    /// 被约束的instance行数
+    pub instance_rows: usize,
+    /// 每个多项式末尾保留的blinding行数
+    pub blinding_rows: usize,
+    /// 能容纳电路的最小k
+    pub minimal_k: u32,
+}
+
+impl CircuitStats {
+    /// 测量电路（witness不影响布局，可以传入 `without_witnesses()` 的结果）
+    pub fn measure<F: Field, C: Circuit<F>>(circuit: &C) -> Result<Self, Error> {
+        let mut meta = ConstraintSystem::<F>::default();
+        let config = C::configure(&mut meta);
+
+        let mut counter = RowCounter::default();
+        C::FloorPlanner::synthesize(&mut counter, circuit, config, meta.constants().clone())?;
+
+        let (table_rows, region_fixed_rows) =
+            counter
+                .fixed_rows
+                .iter()
+                .fold((0, 0), |(table, region), (column, rows)| {
+                    if counter.table_columns.contains(column) {
+                        (table.max(*rows), region)
+                    } else {
+                        (table, region.max(*rows))
+                    }
+                });
+        let region_rows = counter
+            .advice_rows
+            .max(counter.selector_rows)
+            .max(region_fixed_rows);
+
+        let blinding_rows = meta.blinding_factors();
+        let rows_used = region_rows.max(table_rows).max(counter.instance_rows);
+
+        // 可用行 = 2^k - (blinding行 + 1)，同时不能少于约束系统要求的最小行数
+        let rows_needed = (rows_used + blinding_rows + 1).max(meta.minimum_rows());
+        let minimal_k = rows_needed.next_power_of_two().trailing_zeros();
+
+        Ok(CircuitStats {
+            advice_columns: meta.num_advice_columns(),
+            fixed_columns: meta.num_fixed_columns(),
+            instance_columns: meta.num_instance_columns(),
+            selectors: meta.num_selectors(),
+            lookups: meta.lookups().len(),
+            gates: meta.gates().len(),
+            constraints: meta.gates().iter().map(|g| g.polynomials().len()).sum(),
+            max_degree: meta.degree(),
+            regions: counter.regions,
+            region_rows,
+            table_rows,
+            instance_rows: counter.instance_rows,
+            blinding_rows,
+            minimal_k,
+        })
+    }
+
+    /// 电路实际占用的行数
+    pub fn rows_used(&self) -> usize {
+        self.region_rows
+            .max(self.table_rows)
+            .max(self.instance_rows)
+    }
+
+    /// 给定k时可以分配的行数
+    pub fn usable_rows(&self, k: u32) -> usize {
+        (1usize << k).saturating_sub(self.blinding_rows + 1)
+    }
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "columns:     advice={} fixed={} instance={} selectors={}",
+            self.advice_columns, self.fixed_columns, self.instance_columns, self.selectors
+        )?;
+        writeln!(
+            f,
+            "gates:       {} ({} constraints)",
+            self.gates, self.constraints
+        )?;
+        writeln!(f, "lookups:     {}", self.lookups)?;
+        writeln!(f, "max degree:  {}", self.max_degree)?;
+        writeln!(f, "regions:     {}", self.regions)?;
+        writeln!(
+            f,
+            "rows:        regions={} tables={} instance={} blinding={}",
+            self.region_rows, self.table_rows, self.instance_rows, self.blinding_rows
+        )?;
+        write!(
+            f,
+            "minimal k:   {} ({} usable rows)",
+            self.minimal_k,
+            self.usable_rows(self.minimal_k)
+        )
+    }
+}
+
+/// 只记录行占用、不保存任何值的Assignment实现
+#[derive(Default)]
+struct RowCounter {
+    regions: usize,
+    advice_rows: usize,
+    selector_rows: usize,
+    instance_rows: usize,
+    /// 每个fixed列占用的行数
+    fixed_rows: HashMap<Column<Fixed>, usize>,
+    /// `assign_table` 结束时floor planner会对table列调用 `fill_from_row`，借此区分table列
+    table_columns: HashSet<Column<Fixed>>,
+}
+
+impl<F: Field> Assignment<F> for RowCounter {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.regions += 1;
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.selector_rows = self.selector_rows.max(row + 1);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.advice_rows = self.advice_rows.max(row + 1);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let rows = self.fixed_rows.entry(column).or_default();
+        *rows = (*rows).max(row + 1);
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        for (column, row) in [(left_column, left_row), (right_column, right_row)] {
+            if *column.column_type() == Any::Instance {
+                self.instance_rows = self.instance_rows.max(row + 1);
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        self.table_columns.insert(column);
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        basic::basic_chip::SquareSumCircuit,
+        lookup::large_range_analysis::{BinaryRangeCircuit, BitDecompositionCircuit},
+    };
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+
+    #[test]
+    fn test_square_sum_stats() {
+        let a = Fp::from(3);
+        let b = Fp::from(4);
+        let circuit = SquareSumCircuit {
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+
+        let stats = CircuitStats::measure(&circuit).unwrap();
+        println!("{stats}");
+
+        assert_eq!(stats.advice_columns, 3);
+        assert_eq!(stats.instance_columns, 1);
+        assert_eq!(stats.selectors, 2);
+        assert_eq!(stats.gates, 2);
+        assert_eq!(stats.lookups, 0);
+        assert_eq!(stats.table_rows, 0);
+        assert_eq!(stats.instance_rows, 1);
+        assert!(stats.rows_used() <= stats.usable_rows(stats.minimal_k));
+        assert!(stats.rows_used() > stats.usable_rows(stats.minimal_k - 1));
+
+        // 最小k刚好能通过MockProver，再小一位就放不下
+        let c = a * a + b * b;
+        let prover = MockProver::run(stats.minimal_k, &circuit, vec![vec![c]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        assert!(MockProver::run(stats.minimal_k - 1, &circuit, vec![vec![c]]).is_err());
+    }
+
+    #[test]
+    fn test_range_circuit_stats() {
+        // 位分解方案：256行的字节表决定了k
+        let circuit = BitDecompositionCircuit::<Fp>::new(0x12345678);
+        let stats = CircuitStats::measure(&circuit).unwrap();
+        assert_eq!(stats.table_rows, 256);
+        assert_eq!(stats.lookups, 4);
+        assert_eq!(stats.region_rows, 1);
+        let prover = MockProver::run(stats.minimal_k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // 二进制方案：33个advice列，没有table，行数很少
+        let circuit = BinaryRangeCircuit::<Fp>::new(0xFFFFFFFF);
+        let stats = CircuitStats::measure(&circuit).unwrap();
+        assert_eq!(stats.advice_columns, 33);
+        assert_eq!(stats.table_rows, 0);
+        assert_eq!(stats.gates, 33);
+        let prover = MockProver::run(stats.minimal_k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}